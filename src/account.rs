@@ -0,0 +1,260 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use serde_json::Value;
+use stellar_xdr::curr::{AccountEntry, AccountEntryExt, AccountEntryExtensionV1Ext, AccountFlags, LedgerEntryData};
+use xshell::{cmd, Shell};
+
+use crate::commands::commands::execute;
+use crate::commands::commands::StellarCliCmdName::KeysAddress;
+use crate::network::{StellarNetwork, BASE_RESERVE};
+use crate::{ledger_key, rpc};
+
+/// Spendable balance under which Soroban fees may no longer be covered.
+const FEE_BUFFER: i64 = 10_000_000;
+const STROOPS_PER_XLM: f64 = 10_000_000.0;
+
+/// Resolves a CLI identity to its address, addresses are returned as is.
+pub fn resolve(account: &str) -> anyhow::Result<String> {
+    if ledger_key::parse_account_id(account).is_ok() {
+        return Ok(account.to_string());
+    }
+    let res = execute(KeysAddress(account.to_string()));
+    if !res.success {
+        bail!("`{account}` is neither an address nor a known identity: {}", res.stderr);
+    }
+    Ok(res.result.trim().to_string())
+}
+
+/// Funds an account with the friendbot at `friendbot_url` and returns the funding transaction hash.
+pub fn fund(friendbot_url: &str, account_id: &str) -> anyhow::Result<String> {
+    let url = format!("{friendbot_url}?addr={account_id}");
+    let sh = Shell::new()?;
+    let raw = cmd!(sh, "curl --silent --show-error {url}")
+        .timeout(Duration::from_secs(30))
+        .read()
+        .with_context(|| format!("request to {friendbot_url} failed"))?;
+    let response: Value = serde_json::from_str(&raw).with_context(|| format!("friendbot returned `{raw}`"))?;
+
+    match response["hash"].as_str() {
+        Some(hash) => Ok(hash.to_string()),
+        None => {
+            let detail = response["detail"].as_str().or(response["title"].as_str()).unwrap_or(raw.as_str());
+            bail!("friendbot refused to fund {account_id}: {detail}")
+        }
+    }
+}
+
+/// Balance an account has to keep and what it can spend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Reserve {
+    minimum_balance: i64,
+    /// Balance above the minimum balance and the selling liabilities of open offers.
+    available: i64,
+    num_sponsored: u32,
+    num_sponsoring: u32,
+}
+
+impl Reserve {
+    /// The account and each subentry lock a base reserve, entries it sponsors are paid by the
+    /// account and entries sponsored for it are paid by their sponsor.
+    fn of(account: &AccountEntry) -> Self {
+        let (selling_liabilities, num_sponsored, num_sponsoring) = match &account.ext {
+            AccountEntryExt::V0 => (0, 0, 0),
+            AccountEntryExt::V1(v1) => match &v1.ext {
+                AccountEntryExtensionV1Ext::V0 => (v1.liabilities.selling, 0, 0),
+                AccountEntryExtensionV1Ext::V2(v2) => (v1.liabilities.selling, v2.num_sponsored, v2.num_sponsoring),
+            },
+        };
+        let reserve_entries =
+            2 + i64::from(account.num_sub_entries) + i64::from(num_sponsoring) - i64::from(num_sponsored);
+        let minimum_balance = reserve_entries * i64::from(BASE_RESERVE);
+        Reserve {
+            minimum_balance,
+            available: account.balance - minimum_balance - selling_liabilities,
+            num_sponsored,
+            num_sponsoring,
+        }
+    }
+
+    /// Whether the spendable balance may no longer cover Soroban fees.
+    fn is_low(self) -> bool {
+        self.available < FEE_BUFFER
+    }
+}
+
+/// Fetches an account entry and renders its balance, thresholds, signers and trustlines.
+pub fn render(network: StellarNetwork, account_id: &str) -> anyhow::Result<Vec<Line<'static>>> {
+    let key = ledger_key::account_key(account_id)?;
+    let ledger_entries = rpc::get_ledger_entries(network, &[key])?;
+    let Some(LedgerEntryData::Account(account)) = ledger_entries.entries.first().map(|entry| &entry.data) else {
+        bail!("account {account_id} does not exist on {network}, fund it first");
+    };
+
+    let reserve = Reserve::of(account);
+
+    let [master, low, medium, high] = account.thresholds.0;
+    let mut lines = vec![
+        Line::from(format!("Account {account_id} on {network}").bold()),
+        Line::raw(format!(
+            "Balance: {} XLM, minimum balance: {} XLM, available: {} XLM",
+            xlm(account.balance),
+            xlm(reserve.minimum_balance),
+            xlm(reserve.available)
+        )),
+        Line::raw(format!("Sequence number: {}", account.seq_num.0)),
+        Line::raw(format!(
+            "Subentries: {}, sponsoring: {}, sponsored: {}",
+            account.num_sub_entries, reserve.num_sponsoring, reserve.num_sponsored
+        )),
+        Line::raw(format!("Thresholds: master {master}, low {low}, medium {medium}, high {high}")),
+        Line::raw(format!("Flags: {}", flags(account))),
+    ];
+    let home_domain = account.home_domain.to_utf8_string_lossy();
+    if !home_domain.is_empty() {
+        lines.push(Line::raw(format!("Home domain: {home_domain}")));
+    }
+    if reserve.is_low() {
+        lines.push(Line::from(
+            format!("Only {} XLM above the reserve, transactions may fail to pay their fees", xlm(reserve.available))
+                .red()
+                .bold(),
+        ));
+    }
+
+    lines.push(Line::raw(""));
+    lines.push(Line::from(format!("Signers ({})", account.signers.len() + 1).bold()));
+    lines.push(Line::raw(format!("{account_id}  weight {master} (master key)")));
+    lines.extend(
+        account
+            .signers
+            .iter()
+            .map(|signer| Line::raw(format!("{}  weight {}", signer.key, signer.weight))),
+    );
+
+    lines.push(Line::raw(""));
+    match trustlines(network, account_id) {
+        Ok(trustlines) => {
+            lines.push(Line::from(format!("Trustlines ({})", trustlines.len()).bold()));
+            lines.extend(trustlines);
+        }
+        Err(error) => lines.push(Line::from(format!("Trustlines unavailable: {error:#}").dim())),
+    }
+    Ok(lines)
+}
+
+/// Lists the account's trustlines from Horizon, Soroban RPC can only fetch known keys.
+fn trustlines(network: StellarNetwork, account_id: &str) -> anyhow::Result<Vec<Line<'static>>> {
+    let url = format!("{}/accounts/{account_id}", network.horizon_url());
+    let sh = Shell::new()?;
+    let raw = cmd!(sh, "curl --silent --show-error --fail {url}")
+        .timeout(Duration::from_secs(10))
+        .read()
+        .with_context(|| format!("request to {url} failed"))?;
+    let account: Value = serde_json::from_str(&raw).context("Horizon returned an invalid response")?;
+
+    Ok(account["balances"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter(|balance| balance["asset_type"] != "native")
+        .map(|balance| {
+            let asset = match balance["asset_type"].as_str() {
+                Some("liquidity_pool_shares") => {
+                    format!("pool {}", balance["liquidity_pool_id"].as_str().unwrap_or_default())
+                }
+                _ => format!(
+                    "{}:{}",
+                    balance["asset_code"].as_str().unwrap_or_default(),
+                    balance["asset_issuer"].as_str().unwrap_or_default()
+                ),
+            };
+            let line = format!(
+                "{asset}  balance {}  limit {}",
+                balance["balance"].as_str().unwrap_or_default(),
+                balance["limit"].as_str().unwrap_or_default()
+            );
+            if balance["is_authorized"].as_bool() == Some(false) {
+                Line::from(format!("{line}  (not authorized)").yellow())
+            } else {
+                Line::raw(line)
+            }
+        })
+        .collect())
+}
+
+fn flags(account: &AccountEntry) -> String {
+    let flags: Vec<&str> = [
+        (AccountFlags::RequiredFlag, "auth required"),
+        (AccountFlags::RevocableFlag, "auth revocable"),
+        (AccountFlags::ImmutableFlag, "auth immutable"),
+        (AccountFlags::ClawbackEnabledFlag, "clawback enabled"),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| account.flags & *flag as u32 != 0)
+    .map(|(_, name)| name)
+    .collect();
+    if flags.is_empty() { "none".to_string() } else { flags.join(", ") }
+}
+
+fn xlm(stroops: i64) -> String {
+    format!("{:.7}", stroops as f64 / STROOPS_PER_XLM)
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_xdr::curr::{AccountEntryExtensionV1, AccountEntryExtensionV2, AccountEntryExtensionV2Ext, Liabilities};
+
+    use super::*;
+
+    fn account(balance: i64, num_sub_entries: u32, ext: AccountEntryExt) -> AccountEntry {
+        AccountEntry { balance, num_sub_entries, ext, ..Default::default() }
+    }
+
+    fn with_liabilities(selling: i64, ext: AccountEntryExtensionV1Ext) -> AccountEntryExt {
+        AccountEntryExt::V1(AccountEntryExtensionV1 { liabilities: Liabilities { buying: 0, selling }, ext })
+    }
+
+    fn sponsorships(num_sponsored: u32, num_sponsoring: u32) -> AccountEntryExtensionV1Ext {
+        AccountEntryExtensionV1Ext::V2(AccountEntryExtensionV2 {
+            num_sponsored,
+            num_sponsoring,
+            ext: AccountEntryExtensionV2Ext::V0,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn account_and_subentries_lock_a_base_reserve_each() {
+        let reserve = Reserve::of(&account(100_000_000, 3, AccountEntryExt::V0));
+        assert_eq!(reserve.minimum_balance, 25_000_000);
+        assert_eq!(reserve.available, 75_000_000);
+    }
+
+    #[test]
+    fn selling_liabilities_are_not_available() {
+        let reserve = Reserve::of(&account(100_000_000, 1, with_liabilities(20_000_000, AccountEntryExtensionV1Ext::V0)));
+        assert_eq!(reserve.minimum_balance, 15_000_000);
+        assert_eq!(reserve.available, 65_000_000);
+    }
+
+    #[test]
+    fn sponsoring_adds_reserves_and_being_sponsored_removes_them() {
+        let reserve = Reserve::of(&account(100_000_000, 4, with_liabilities(0, sponsorships(2, 3))));
+        // 2 + 4 subentries + 3 sponsoring - 2 sponsored.
+        assert_eq!(reserve.minimum_balance, 35_000_000);
+        assert_eq!(reserve.available, 65_000_000);
+        assert_eq!((reserve.num_sponsored, reserve.num_sponsoring), (2, 3));
+    }
+
+    #[test]
+    fn balance_close_to_the_reserve_is_flagged() {
+        let reserve = Reserve::of(&account(15_000_000, 0, AccountEntryExt::V0));
+        assert_eq!(reserve.available, 5_000_000);
+        assert!(reserve.is_low());
+        assert!(!Reserve::of(&account(20_000_000, 0, AccountEntryExt::V0)).is_low());
+    }
+}
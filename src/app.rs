@@ -1,14 +1,25 @@
+use std::collections::HashMap;
 use std::error;
 use std::fmt::Debug;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
+use anyhow::anyhow;
 use ratatui::style::palette::tailwind;
 use ratatui::style::{Modifier, Style, Stylize};
 use ratatui::text::{Line, Text, ToText};
 pub(crate) use ratatui::widgets::ListState;
 use ratatui::widgets::ScrollbarState;
+use stellar_xdr::curr::LedgerKey;
 use strum::{Display, EnumIter, FromRepr};
 
 use crate::app::SelectedTab::{Tab1, Tab2, Tab3, Tab4};
+use crate::commands::commands::{CmdResponse, ContractDataArgs, DEFAULT_CONTRACT_ID};
+use crate::network::StellarNetwork;
+use crate::profile::CostSample;
+use crate::token::TokenSpec;
+use crate::transaction;
 use crate::ui::layout::CmdOutputScrollbar;
 
 /// Application result type.
@@ -47,6 +58,18 @@ impl ListStates {
         }
     }
 
+    pub fn selected(
+        selected_tab: SelectedTab,
+        list_states: &ListStates,
+    ) -> Option<usize> {
+        match selected_tab {
+            Tab1 => list_states.list_state.selected(),
+            Tab2 => list_states.list_state2.selected(),
+            Tab3 => list_states.list_state3.selected(),
+            Tab4 => list_states.list_state4.selected(),
+        }
+    }
+
     pub fn new(
         list_state: ListState,
         list_state2: ListState,
@@ -70,7 +93,8 @@ pub struct CmdOutputState<'a> {
     pub cmd_output: Box<Text<'a>>,
     pub network_status: Box<Text<'a>>,
     pub cmd_output_state: Box<ListState>,
-    pub cmd_output_scrollbar: Box<ScrollbarState>
+    pub cmd_output_scrollbar: Box<ScrollbarState>,
+    pub cmd_output_scroll: u16,
 }
 
 impl CmdOutputState<'static> {
@@ -81,10 +105,258 @@ impl CmdOutputState<'static> {
                     .style(Style::default().add_modifier(Modifier::DIM))),
             cmd_output_state,
             cmd_output_scrollbar: Box::new(ScrollbarState::new(100)),
+            cmd_output_scroll: 0,
+        }
+    }
+}
+
+/// An input field of a [`Form`].
+///
+/// Fields with `options` are pickers cycled with left and right instead of free text.
+#[derive(Debug, Clone, Default)]
+pub struct FormField {
+    pub label: &'static str,
+    pub value: String,
+    pub options: Vec<String>,
+}
+
+impl FormField {
+    pub fn text(label: &'static str, value: &str) -> Self {
+        Self {
+            label,
+            value: value.to_string(),
+            options: vec![],
+        }
+    }
+
+    pub fn picker<S: AsRef<str>>(label: &'static str, options: &[S]) -> Self {
+        let options: Vec<String> = options.iter().map(|option| option.as_ref().to_string()).collect();
+        Self {
+            label,
+            value: options.first().cloned().unwrap_or_default(),
+            options,
+        }
+    }
+
+    /// Pre-fill the field, picker values are only taken if they are one of the options.
+    pub fn with_value(mut self, value: &str) -> Self {
+        if !self.is_picker() || self.options.iter().any(|option| option == value) {
+            self.value = value.to_string();
+        }
+        self
+    }
+
+    pub fn is_picker(&self) -> bool {
+        !self.options.is_empty()
+    }
+
+    /// Select the next or previous option of a picker.
+    pub fn cycle(&mut self, forward: bool) {
+        let len = self.options.len();
+        if len == 0 {
+            return;
         }
+        let current = self.options.iter().position(|o| *o == self.value).unwrap_or(0);
+        let next = if forward { (current + 1) % len } else { (current + len - 1) % len };
+        self.value = self.options[next].clone();
     }
 }
 
+/// A form collecting the inputs of a [`FormKind`] before it is submitted.
+#[derive(Debug, Clone)]
+pub struct Form {
+    pub kind: FormKind,
+    pub fields: Vec<FormField>,
+    pub active: usize,
+}
+
+impl Form {
+    pub fn new(kind: FormKind, fields: Vec<FormField>) -> Self {
+        Self { kind, fields, active: 0 }
+    }
+
+    pub fn next_field(&mut self) {
+        self.active = (self.active + 1) % self.fields.len().max(1);
+    }
+
+    pub fn previous_field(&mut self) {
+        let len = self.fields.len().max(1);
+        self.active = (self.active + len - 1) % len;
+    }
+
+    pub fn active_field_mut(&mut self) -> Option<&mut FormField> {
+        self.fields.get_mut(self.active)
+    }
+
+    /// Trimmed value of the field with the given label.
+    pub fn value(&self, label: &str) -> &str {
+        self.fields
+            .iter()
+            .find(|field| field.label == label)
+            .map(|field| field.value.trim())
+            .unwrap_or_default()
+    }
+
+    pub fn parse<T: FromStr>(&self, label: &str) -> anyhow::Result<T> {
+        let value = self.value(label);
+        value
+            .replace('_', "")
+            .parse()
+            .map_err(|_| anyhow!("invalid {label}: `{value}`"))
+    }
+}
+
+#[derive(Clone, Copy, Display, Debug, PartialEq, Eq)]
+pub enum FormKind {
+    #[strum(to_string = "Rent Fee Calculator")]
+    RentFeeCalculator,
+    #[strum(to_string = "Read Contract Data")]
+    ReadContractData,
+    #[strum(to_string = "Extend TTL")]
+    ExtendTtl,
+    #[strum(to_string = "Restore Archived Entry")]
+    RestoreEntry,
+    #[strum(to_string = "Generate Data Key")]
+    GenerateDataKey,
+    #[strum(to_string = "Ledger Entry Explorer")]
+    LedgerEntryExplorer,
+    #[strum(to_string = "Contract Events")]
+    ContractEvents,
+    #[strum(to_string = "Transaction Lookup")]
+    TransactionLookup,
+    #[strum(to_string = "Invoke Contract Function")]
+    InvokeContract,
+    #[strum(to_string = "Deploy Contract: Upload Wasm")]
+    UploadWasm,
+    #[strum(to_string = "Deploy Contract: Deploy")]
+    DeployContract,
+    #[strum(to_string = "Upgrade Contract: Compare")]
+    CompareUpgrade,
+    #[strum(to_string = "Upgrade Contract: Confirm")]
+    ConfirmUpgrade,
+    #[strum(to_string = "Inspect Contract Wasm")]
+    InspectWasm,
+    #[strum(to_string = "Build Contract")]
+    BuildContract,
+    #[strum(to_string = "Deploy Build Artifact")]
+    DeployArtifact,
+    #[strum(to_string = "Generate Client Bindings")]
+    GenerateBindings,
+    #[strum(to_string = "Stellar Asset Contract")]
+    AssetContract,
+    #[strum(to_string = "Token Dashboard")]
+    TokenDashboard,
+    #[strum(to_string = "Account Inspector")]
+    AccountInspector,
+    #[strum(to_string = "Fund Account")]
+    FundAccount,
+    #[strum(to_string = "Review Authorizations")]
+    ReviewAuthorizations,
+    #[strum(to_string = "Sign Authorizations")]
+    SignAuthorizations,
+    #[strum(to_string = "Preview Footprint")]
+    PreviewFootprint,
+    #[strum(to_string = "Save Unsigned Transaction")]
+    SaveTransaction,
+    #[strum(to_string = "Submit Signed Transaction")]
+    SubmitSignedTransaction,
+    #[strum(to_string = "Capture Sandbox Snapshot")]
+    CaptureSnapshot,
+    #[strum(to_string = "Run in Sandbox")]
+    RunInSandbox,
+    #[strum(to_string = "Snapshot Contract Storage")]
+    SnapshotStorage,
+    #[strum(to_string = "Diff Storage Snapshots")]
+    DiffStorage,
+    #[strum(to_string = "Browse Instance Storage")]
+    BrowseInstanceStorage,
+}
+
+/// Scripts listed in the tab menus.
+#[derive(Clone, Copy, Display, Debug, PartialEq, Eq)]
+pub enum MenuItem {
+    #[strum(to_string = "Extend Instance TTL")]
+    ExtendInstanceTtl,
+    #[strum(to_string = "Extend Persistence TTL")]
+    ExtendPersistenceTtl,
+    #[strum(to_string = "Extend Temporary TTL")]
+    ExtendTemporaryTtl,
+    #[strum(to_string = "Generate Data Key")]
+    GenerateDataKey,
+    #[strum(to_string = "Rent Fee Calculator")]
+    RentFeeCalculator,
+    #[strum(to_string = "Restore Persistent Storage")]
+    RestorePersistentStorage,
+    #[strum(to_string = "Restore Contract Instance")]
+    RestoreContractInstance,
+    #[strum(to_string = "Restore Contract Code Hash")]
+    RestoreContractCodeHash,
+    #[strum(to_string = "Restore Instance Storage")]
+    RestoreInstanceStorage,
+    #[strum(to_string = "Invoke Contract Function")]
+    InvokeContract,
+    #[strum(to_string = "Deploy Contract")]
+    DeployContract,
+    #[strum(to_string = "Upgrade Contract")]
+    UpgradeContract,
+    #[strum(to_string = "Token Dashboard")]
+    TokenDashboard,
+    #[strum(to_string = "Build Contract")]
+    BuildContract,
+    #[strum(to_string = "Deploy Build Artifact")]
+    DeployArtifact,
+    #[strum(to_string = "Review Authorizations")]
+    ReviewAuthorizations,
+    #[strum(to_string = "Submit Signed Transaction")]
+    SubmitSignedTransaction,
+    #[strum(to_string = "Preview Footprint")]
+    PreviewFootprint,
+    #[strum(to_string = "Capture Sandbox Snapshot")]
+    CaptureSnapshot,
+    #[strum(to_string = "Run in Sandbox")]
+    RunInSandbox,
+    #[strum(to_string = "Set Persistent Data")]
+    SetPersistentData,
+    #[strum(to_string = "Set Instance Data")]
+    SetInstanceData,
+    #[strum(to_string = "Extend Persistent TTL")]
+    InvokeExtendPersistentTtl,
+    #[strum(to_string = "Extend Instance TTL")]
+    InvokeExtendInstanceTtl,
+    #[strum(to_string = "Show Contract Data")]
+    ShowContractData,
+    #[strum(to_string = "Browse Instance Storage")]
+    BrowseInstanceStorage,
+    #[strum(to_string = "Show Contract Events")]
+    ShowContractEvents,
+    #[strum(to_string = "Show Storage TTLs")]
+    ShowStorageTtls,
+    #[strum(to_string = "Show Misc data")]
+    ShowMiscData,
+    #[strum(to_string = "Show Network Config")]
+    ShowNetworkConfig,
+    #[strum(to_string = "Explore Ledger Entry")]
+    ExploreLedgerEntry,
+    #[strum(to_string = "Snapshot Contract Storage")]
+    SnapshotStorage,
+    #[strum(to_string = "Diff Storage Snapshots")]
+    DiffStorage,
+    #[strum(to_string = "Look Up Transaction")]
+    LookUpTransaction,
+    #[strum(to_string = "Inspect Contract Wasm")]
+    InspectContractWasm,
+    #[strum(to_string = "Generate Client Bindings")]
+    GenerateBindings,
+    #[strum(to_string = "Show Asset Contract")]
+    ShowAssetContract,
+    #[strum(to_string = "Inspect Account")]
+    InspectAccount,
+    #[strum(to_string = "Fund Account")]
+    FundAccount,
+    #[strum(to_string = "Show Cost Profile")]
+    ShowCostProfile,
+}
+
 /// Application.
 
 pub struct App<'a> {
@@ -98,6 +370,63 @@ pub struct App<'a> {
     pub list_states: Box<ListStates>,
 
     pub cmd_output_state: CmdOutputState<'a>,
+
+    /// Network the scripts run against.
+    pub network: StellarNetwork,
+
+    /// Form being filled in, receives all key presses while open.
+    pub form: Option<Form>,
+
+    /// Contract the scripts run against.
+    pub contract_id: String,
+
+    /// Identity signing transactions, the CLI default identity when empty.
+    pub source_account: String,
+
+    /// Archived entry found by the last read, restored with `r`.
+    pub pending_restore: Option<ContractDataArgs>,
+
+    /// Last key built with "Generate Data Key", pre-filled in the read, extend and restore scripts.
+    pub data_key: Option<ContractDataArgs>,
+
+    /// Stop flag of the events follow mode, set while new events are being appended.
+    pub events_follow: Option<Arc<AtomicBool>>,
+
+    /// Hash of the last transaction the CLI submitted, pre-filled in the transaction lookup.
+    pub tx_hash: String,
+
+    /// Last wasm file uploaded, pre-filled in the deploy and upgrade flows.
+    pub wasm_path: String,
+
+    /// Cargo workspace of the contracts built with "Build Contract".
+    pub workspace: String,
+
+    /// Account shown by the account inspector, refreshed with `a`.
+    pub inspected_account: Option<String>,
+
+    /// Friendbots used instead of the networks' defaults, e.g. a local stand-in.
+    pub friendbot_urls: HashMap<StellarNetwork, String>,
+
+    /// Build and simulate transactions without submitting them, toggled with `o`.
+    pub build_only: bool,
+
+    /// Last transaction built for signing elsewhere, saved with `s` and copied with `y`.
+    pub unsigned_tx: Option<String>,
+
+    /// Costs of the invocations simulated or submitted so far, oldest first.
+    pub cost_samples: Vec<CostSample>,
+
+    /// Ledger snapshot the sandbox last captured into or ran against.
+    pub snapshot_path: String,
+
+    /// Contract data keys seen in footprints and reads, included in storage snapshots.
+    pub storage_keys: Vec<LedgerKey>,
+
+    /// Path of the instance storage value last browsed, drilled into with `i`.
+    pub instance_path: Option<String>,
+
+    /// Spec of the contract the token dashboard last opened.
+    pub token_spec: Option<TokenSpec>,
 }
 
 #[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
@@ -145,6 +474,60 @@ impl SelectedTab {
             .into()
     }
 
+    /// Scripts listed in the tab's menu, in display order.
+    pub fn menu_items(self) -> &'static [MenuItem] {
+        match self {
+            Tab1 => &[
+                MenuItem::ExtendInstanceTtl,
+                MenuItem::ExtendPersistenceTtl,
+                MenuItem::ExtendTemporaryTtl,
+                MenuItem::GenerateDataKey,
+                MenuItem::RentFeeCalculator,
+            ],
+            Tab2 => &[
+                MenuItem::RestorePersistentStorage,
+                MenuItem::RestoreContractInstance,
+                MenuItem::RestoreContractCodeHash,
+                MenuItem::RestoreInstanceStorage,
+            ],
+            Tab3 => &[
+                MenuItem::InvokeContract,
+                MenuItem::DeployContract,
+                MenuItem::UpgradeContract,
+                MenuItem::TokenDashboard,
+                MenuItem::BuildContract,
+                MenuItem::DeployArtifact,
+                MenuItem::ReviewAuthorizations,
+                MenuItem::PreviewFootprint,
+                MenuItem::SubmitSignedTransaction,
+                MenuItem::CaptureSnapshot,
+                MenuItem::RunInSandbox,
+                MenuItem::SetPersistentData,
+                MenuItem::SetInstanceData,
+                MenuItem::InvokeExtendPersistentTtl,
+                MenuItem::InvokeExtendInstanceTtl,
+            ],
+            Tab4 => &[
+                MenuItem::ShowContractData,
+                MenuItem::BrowseInstanceStorage,
+                MenuItem::ShowContractEvents,
+                MenuItem::ShowStorageTtls,
+                MenuItem::ShowMiscData,
+                MenuItem::ShowNetworkConfig,
+                MenuItem::ExploreLedgerEntry,
+                MenuItem::SnapshotStorage,
+                MenuItem::DiffStorage,
+                MenuItem::LookUpTransaction,
+                MenuItem::InspectContractWasm,
+                MenuItem::GenerateBindings,
+                MenuItem::ShowAssetContract,
+                MenuItem::InspectAccount,
+                MenuItem::FundAccount,
+                MenuItem::ShowCostProfile,
+            ],
+        }
+    }
+
     pub const fn palette(self) -> tailwind::Palette {
         match self {
             Tab1 => tailwind::YELLOW,
@@ -177,6 +560,25 @@ impl Default for App<'_> {
                 Box::new(Text::raw("")),
                 Box::new(ListState::default().with_offset(0).with_selected(Some(0))),
             ),
+            network: StellarNetwork::default(),
+            form: None,
+            contract_id: DEFAULT_CONTRACT_ID.to_string(),
+            source_account: String::new(),
+            pending_restore: None,
+            data_key: None,
+            events_follow: None,
+            tx_hash: String::new(),
+            wasm_path: String::new(),
+            workspace: String::from("."),
+            inspected_account: None,
+            friendbot_urls: HashMap::new(),
+            build_only: false,
+            unsigned_tx: None,
+            cost_samples: Vec::new(),
+            snapshot_path: String::from("snapshot.json"),
+            storage_keys: Vec::new(),
+            instance_path: None,
+            token_spec: None,
         }
     }
 }
@@ -201,4 +603,92 @@ impl App<'_> {
     pub fn previous_tab(&mut self) {
         self.selected_tab = self.selected_tab.previous();
     }
+
+    /// Whether a menu item is known not to apply to the current contract, the token dashboard
+    /// once the contract's spec shows it isn't a SEP-41 token.
+    pub fn menu_item_disabled(&self, menu_item: MenuItem) -> bool {
+        match menu_item {
+            MenuItem::TokenDashboard => self
+                .token_spec
+                .as_ref()
+                .is_some_and(|spec| spec.is_for(self.network, &self.contract_id) && !spec.missing_functions().is_empty()),
+            _ => false,
+        }
+    }
+
+    /// Menu item highlighted in the current tab.
+    pub fn selected_menu_item(&self) -> Option<MenuItem> {
+        let index = ListStates::selected(self.selected_tab, &self.list_states)?;
+        let items = self.selected_tab.menu_items();
+        // The list state may point past the end until the list is rendered again.
+        items.get(index.min(items.len().saturating_sub(1))).copied()
+    }
+
+    /// Appends lines to the command output and scrolls to the first of them.
+    pub fn print_lines(&mut self, lines: impl IntoIterator<Item = Line<'static>>) {
+        let output = &mut self.cmd_output_state;
+        let first_line = output.cmd_output.lines.len();
+        for line in lines {
+            output.cmd_output.push_line(line);
+        }
+        output.cmd_output_scroll = u16::try_from(first_line).unwrap_or(u16::MAX);
+        *output.cmd_output_scrollbar = output
+            .cmd_output_scrollbar
+            .content_length(output.cmd_output.lines.len())
+            .position(first_line);
+    }
+
+    /// Prints a command line followed by its output, stderr is only shown when it failed.
+    pub fn print_cmd_response(&mut self, res: &CmdResponse) {
+        let mut lines = vec![Line::from(format!("$ {}", res.raw_cmd).dim())];
+        lines.extend(res.result.lines().map(|line| Line::raw(line.to_string())));
+        if !res.success {
+            lines.extend(res.stderr.lines().map(|line| Line::from(line.to_string().red())));
+        }
+        if let Some(hash) = transaction::find_hash(&res.stderr).or_else(|| transaction::find_hash(&res.result)) {
+            self.tx_hash = hash;
+        }
+        self.print_lines(lines);
+    }
+
+    pub fn print_error(&mut self, error: anyhow::Error) {
+        self.print_lines([Line::from(format!("Error: {error:#}").red())]);
+    }
+
+    /// Prints the result of a script, or its error.
+    pub fn print_result(&mut self, result: anyhow::Result<Vec<Line<'static>>>) {
+        match result {
+            Ok(lines) => self.print_lines(lines),
+            Err(error) => self.print_error(error),
+        }
+    }
+
+    pub fn scroll_output(&mut self, forward: bool) {
+        let output = &mut self.cmd_output_state;
+        if forward {
+            output.cmd_output_scroll = output.cmd_output_scroll.saturating_add(1);
+            output.cmd_output_scrollbar.next();
+        } else {
+            output.cmd_output_scroll = output.cmd_output_scroll.saturating_sub(1);
+            output.cmd_output_scrollbar.prev();
+        }
+    }
+
+    /// Stops the events follow mode, returns whether it was running.
+    pub fn stop_following_events(&mut self) -> bool {
+        match self.events_follow.take() {
+            Some(stop) => {
+                stop.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear_output(&mut self) {
+        let output = &mut self.cmd_output_state;
+        output.cmd_output.lines.clear();
+        output.cmd_output_scroll = 0;
+        *output.cmd_output_scrollbar = output.cmd_output_scrollbar.content_length(0).position(0);
+    }
 }
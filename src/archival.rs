@@ -0,0 +1,82 @@
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use stellar_xdr::curr::{ContractDataDurability, LedgerKey};
+
+use crate::network::StellarNetwork;
+use crate::rpc::{self, LedgerEntryResult};
+
+/// Whether a ledger entry can still be read or has to be restored first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryStatus {
+    Live { live_until: u32, remaining: u32 },
+    /// The TTL ran out but the entry has not been evicted from the live state yet.
+    Archived { live_until: u32 },
+    /// Not on the live ledger: never written, evicted to the archive or deleted.
+    NotFound,
+    /// Classic entries don't expire.
+    NoTtl,
+}
+
+impl EntryStatus {
+    pub fn of(entry: &LedgerEntryResult, latest_ledger: u32) -> Self {
+        match entry.live_until_ledger {
+            Some(live_until) if live_until >= latest_ledger => EntryStatus::Live {
+                live_until,
+                remaining: live_until - latest_ledger,
+            },
+            Some(live_until) => EntryStatus::Archived { live_until },
+            None => EntryStatus::NoTtl,
+        }
+    }
+
+    /// Whether the entry has to be restored before it can be used. Entries not found may never
+    /// have been written, so only archived ones qualify.
+    pub fn needs_restore(self, durability: ContractDataDurability) -> bool {
+        durability == ContractDataDurability::Persistent && matches!(self, EntryStatus::Archived { .. })
+    }
+
+    pub fn to_line(self, durability: ContractDataDurability) -> Line<'static> {
+        match (self, durability) {
+            (EntryStatus::Live { live_until, remaining }, _) => {
+                Line::from(format!("Live until ledger {live_until} ({remaining} ledgers left)").green())
+            }
+            (EntryStatus::Archived { live_until }, ContractDataDurability::Persistent) => {
+                Line::from(format!("ARCHIVED: TTL expired at ledger {live_until}, restore before use").red().bold())
+            }
+            (EntryStatus::Archived { live_until }, ContractDataDurability::Temporary) => {
+                Line::from(format!("EXPIRED: temporary entry TTL ended at ledger {live_until}, it can't be restored").red())
+            }
+            (EntryStatus::NotFound, ContractDataDurability::Persistent) => Line::from(
+                "NOT FOUND on the live ledger: the entry was never written or has been archived".yellow(),
+            ),
+            (EntryStatus::NotFound, ContractDataDurability::Temporary) => {
+                Line::from("NOT FOUND: the temporary entry was never written or has expired".yellow())
+            }
+            (EntryStatus::NoTtl, _) => Line::raw("Classic entry, no TTL"),
+        }
+    }
+}
+
+/// Fetches the entry behind `key` and reports whether it is live.
+pub fn check(network: StellarNetwork, key: &LedgerKey) -> anyhow::Result<EntryStatus> {
+    let ledger_entries = rpc::get_ledger_entries(network, std::slice::from_ref(key))?;
+    Ok(ledger_entries
+        .entries
+        .first()
+        .map(|entry| EntryStatus::of(entry, ledger_entries.latest_ledger))
+        .unwrap_or(EntryStatus::NotFound))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_archived_persistent_entries_need_a_restore() {
+        let archived = EntryStatus::Archived { live_until: 90 };
+        assert!(archived.needs_restore(ContractDataDurability::Persistent));
+        assert!(!archived.needs_restore(ContractDataDurability::Temporary));
+        assert!(!EntryStatus::NotFound.needs_restore(ContractDataDurability::Persistent));
+        assert!(!EntryStatus::Live { live_until: 200, remaining: 100 }.needs_restore(ContractDataDurability::Persistent));
+    }
+}
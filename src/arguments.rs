@@ -0,0 +1,528 @@
+use anyhow::{anyhow, bail, Context};
+use serde_json::Value;
+use stellar_xdr::curr::{
+    Duration, ScMap, ScMapEntry, ScSpecEntry, ScSpecFunctionV0, ScSpecTypeDef, ScSpecUdtUnionCaseV0, ScString,
+    ScSymbol, ScVal, ScVec, TimePoint,
+};
+
+use crate::{account, ledger_key, scval, spec};
+
+/// Parses arguments typed as for `contract invoke`, e.g. `--to alice --amount 100`, into the
+/// values `function` takes, without asking the CLI.
+///
+/// Values follow the CLI's syntax: strings, symbols, numbers, addresses and hex bytes are given
+/// as is, vecs, maps, tuples and user types as JSON. Values with spaces are quoted like in a
+/// shell, omitted options are `None` and a bool flag without a value is `true`.
+pub fn parse_args(spec: &[ScSpecEntry], function: &ScSpecFunctionV0, args: &str) -> anyhow::Result<Vec<ScVal>> {
+    let mut named: Vec<(String, Option<String>)> = Vec::new();
+    let mut words = split_words(args)?.into_iter().peekable();
+    while let Some(word) = words.next() {
+        let Some(name) = word.strip_prefix("--") else {
+            bail!("expected `--name value`, found `{word}`");
+        };
+        let (name, value) = match name.split_once('=') {
+            Some((name, value)) => (name.to_string(), Some(value.to_string())),
+            None => (name.to_string(), words.next_if(|next| !next.starts_with("--"))),
+        };
+        named.push((name, value));
+    }
+
+    function
+        .inputs
+        .iter()
+        .map(|input| {
+            let name = input.name.to_utf8_string_lossy();
+            let value = named.iter().find(|(arg, _)| *arg == name).map(|(_, value)| value.as_deref());
+            match (value, &input.type_) {
+                (Some(Some(value)), type_def) => parse_arg(spec, type_def, value).with_context(|| format!("invalid --{name}")),
+                (Some(None), ScSpecTypeDef::Bool) => Ok(ScVal::Bool(true)),
+                (Some(None), type_def) => bail!("missing value for --{name} <{}>", spec::type_name(type_def)),
+                (None, ScSpecTypeDef::Option(_)) => Ok(ScVal::Void),
+                (None, type_def) => bail!("missing --{name} <{}>", spec::type_name(type_def)),
+            }
+        })
+        .collect()
+}
+
+/// Parses one argument value of type `type_def`.
+///
+/// Types written as plain text on the command line are taken verbatim unless quoted as a JSON
+/// string, other types are read as JSON.
+pub fn parse_arg(spec: &[ScSpecEntry], type_def: &ScSpecTypeDef, text: &str) -> anyhow::Result<ScVal> {
+    let value = if is_plain_text(type_def) && !text.starts_with('"') {
+        Value::String(text.to_string())
+    } else {
+        serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+    };
+    parse_json(spec, type_def, &value)
+}
+
+fn is_plain_text(type_def: &ScSpecTypeDef) -> bool {
+    match type_def {
+        ScSpecTypeDef::Option(option) => is_plain_text(&option.value_type),
+        _ => matches!(
+            type_def,
+            ScSpecTypeDef::U32
+                | ScSpecTypeDef::I32
+                | ScSpecTypeDef::U64
+                | ScSpecTypeDef::I64
+                | ScSpecTypeDef::U128
+                | ScSpecTypeDef::I128
+                | ScSpecTypeDef::Timepoint
+                | ScSpecTypeDef::Duration
+                | ScSpecTypeDef::Symbol
+                | ScSpecTypeDef::String
+                | ScSpecTypeDef::Address
+                | ScSpecTypeDef::MuxedAddress
+                | ScSpecTypeDef::Bytes
+                | ScSpecTypeDef::BytesN(_)
+        ),
+    }
+}
+
+/// Converts a JSON value to the `ScVal` of type `type_def`, user types are looked up in `spec`.
+pub fn parse_json(spec: &[ScSpecEntry], type_def: &ScSpecTypeDef, value: &Value) -> anyhow::Result<ScVal> {
+    Ok(match type_def {
+        ScSpecTypeDef::Void if value.is_null() => ScVal::Void,
+        ScSpecTypeDef::Bool => match value {
+            Value::Bool(value) => ScVal::Bool(*value),
+            Value::String(value) => ScVal::Bool(value.parse().map_err(|_| anyhow!("invalid bool `{value}`"))?),
+            other => bail!("expected a bool, found `{other}`"),
+        },
+        ScSpecTypeDef::U32 => ScVal::U32(integer(value)?),
+        ScSpecTypeDef::I32 => ScVal::I32(integer(value)?),
+        ScSpecTypeDef::U64 => ScVal::U64(integer(value)?),
+        ScSpecTypeDef::I64 => ScVal::I64(integer(value)?),
+        ScSpecTypeDef::Timepoint => ScVal::Timepoint(TimePoint(integer(value)?)),
+        ScSpecTypeDef::Duration => ScVal::Duration(Duration(integer(value)?)),
+        ScSpecTypeDef::U128 => ScVal::from(integer::<u128>(value)?),
+        ScSpecTypeDef::I128 => ScVal::from(integer::<i128>(value)?),
+        ScSpecTypeDef::Symbol => symbol(&text(value)?)?,
+        ScSpecTypeDef::String => {
+            let text = text(value)?;
+            ScVal::String(ScString(text.as_str().try_into().map_err(|_| anyhow!("string `{text}` is too long"))?))
+        }
+        ScSpecTypeDef::Address | ScSpecTypeDef::MuxedAddress => address(&text(value)?)?,
+        ScSpecTypeDef::Bytes => ScVal::Bytes(scval::parse_hex(&text(value)?)?.try_into()?),
+        ScSpecTypeDef::BytesN(bytes) => {
+            let decoded = scval::parse_hex(&text(value)?)?;
+            if decoded.len() != bytes.n as usize {
+                bail!("expected {} bytes, found {}", bytes.n, decoded.len());
+            }
+            ScVal::Bytes(decoded.try_into()?)
+        }
+        ScSpecTypeDef::Option(_) if value.is_null() => ScVal::Void,
+        ScSpecTypeDef::Option(option) => parse_json(spec, &option.value_type, value)?,
+        ScSpecTypeDef::Vec(vec) => {
+            let elements = array(value, &spec::type_name(type_def))?
+                .iter()
+                .map(|element| parse_json(spec, &vec.element_type, element))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            ScVal::Vec(Some(ScVec(elements.try_into()?)))
+        }
+        ScSpecTypeDef::Tuple(tuple) => {
+            let elements = array(value, &spec::type_name(type_def))?;
+            if elements.len() != tuple.value_types.len() {
+                bail!("expected {} elements, found {}", tuple.value_types.len(), elements.len());
+            }
+            let elements = tuple
+                .value_types
+                .iter()
+                .zip(elements)
+                .map(|(type_def, element)| parse_json(spec, type_def, element))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            ScVal::Vec(Some(ScVec(elements.try_into()?)))
+        }
+        ScSpecTypeDef::Map(map) => {
+            let Value::Object(object) = value else {
+                bail!("expected a JSON object for {}, found `{value}`", spec::type_name(type_def));
+            };
+            let entries = object
+                .iter()
+                .map(|(key, val)| {
+                    Ok(ScMapEntry {
+                        key: parse_arg(spec, &map.key_type, key)?,
+                        val: parse_json(spec, &map.value_type, val)?,
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            sorted_map(entries)?
+        }
+        ScSpecTypeDef::Udt(udt) => parse_udt(spec, &udt.name.to_utf8_string_lossy(), value)?,
+        other => bail!("{} arguments are not supported", spec::type_name(other)),
+    })
+}
+
+/// Structs are JSON objects, or arrays for tuple structs. Union cases are their name, or an object
+/// from the name to the value, an array for several values. Enums are their value or case name.
+fn parse_udt(spec: &[ScSpecEntry], name: &str, value: &Value) -> anyhow::Result<ScVal> {
+    let entry = spec
+        .iter()
+        .find(|entry| !matches!(entry, ScSpecEntry::FunctionV0(_) | ScSpecEntry::EventV0(_)) && spec::entry_name(entry).1 == name)
+        .ok_or_else(|| anyhow!("the contract has no type `{name}`"))?;
+    Ok(match entry {
+        ScSpecEntry::UdtStructV0(udt) => {
+            let is_tuple = udt.fields.iter().all(|field| field.name.to_utf8_string_lossy().parse::<u32>().is_ok());
+            if is_tuple && !udt.fields.is_empty() {
+                let elements = array(value, name)?;
+                if elements.len() != udt.fields.len() {
+                    bail!("{name} has {} fields, found {}", udt.fields.len(), elements.len());
+                }
+                let elements = udt
+                    .fields
+                    .iter()
+                    .zip(elements)
+                    .map(|(field, element)| parse_json(spec, &field.type_, element))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                ScVal::Vec(Some(ScVec(elements.try_into()?)))
+            } else {
+                let Value::Object(object) = value else {
+                    bail!("expected a JSON object for {name}, found `{value}`");
+                };
+                let entries = udt
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        let field_name = field.name.to_utf8_string_lossy();
+                        let val = object.get(&field_name).with_context(|| format!("{name} needs a `{field_name}` field"))?;
+                        Ok(ScMapEntry {
+                            key: symbol(&field_name)?,
+                            val: parse_json(spec, &field.type_, val).with_context(|| format!("invalid {name}.{field_name}"))?,
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                sorted_map(entries)?
+            }
+        }
+        ScSpecEntry::UdtUnionV0(udt) => {
+            let (case_name, case_value) = match value {
+                Value::String(case_name) => (case_name.as_str(), None),
+                Value::Object(object) if object.len() == 1 => {
+                    let (case_name, case_value) = object.iter().next().context("empty union value")?;
+                    (case_name.as_str(), Some(case_value))
+                }
+                other => bail!("expected a case name or a `{{\"Case\": value}}` object for {name}, found `{other}`"),
+            };
+            let case = udt
+                .cases
+                .iter()
+                .find(|case| match case {
+                    ScSpecUdtUnionCaseV0::VoidV0(case) => case.name.to_utf8_string_lossy() == case_name,
+                    ScSpecUdtUnionCaseV0::TupleV0(case) => case.name.to_utf8_string_lossy() == case_name,
+                })
+                .with_context(|| format!("{name} has no case `{case_name}`"))?;
+            let mut elements = vec![symbol(case_name)?];
+            match (case, case_value) {
+                (ScSpecUdtUnionCaseV0::VoidV0(_), None) => {}
+                (ScSpecUdtUnionCaseV0::TupleV0(case), Some(case_value)) => {
+                    let values = match case.type_.len() {
+                        1 => std::slice::from_ref(case_value),
+                        _ => array(case_value, &format!("{name}::{case_name}"))?,
+                    };
+                    if values.len() != case.type_.len() {
+                        bail!("{name}::{case_name} takes {} values, found {}", case.type_.len(), values.len());
+                    }
+                    for (type_def, value) in case.type_.iter().zip(values) {
+                        elements.push(parse_json(spec, type_def, value)?);
+                    }
+                }
+                (ScSpecUdtUnionCaseV0::VoidV0(_), Some(_)) => bail!("{name}::{case_name} takes no value"),
+                (ScSpecUdtUnionCaseV0::TupleV0(_), None) => bail!("{name}::{case_name} needs a value"),
+            }
+            ScVal::Vec(Some(ScVec(elements.try_into()?)))
+        }
+        ScSpecEntry::UdtEnumV0(udt) => {
+            let case = udt.cases.iter().find(|case| match value {
+                Value::String(case_name) => case.name.to_utf8_string_lossy() == *case_name || case.value.to_string() == *case_name,
+                Value::Number(number) => number.as_u64() == Some(u64::from(case.value)),
+                _ => false,
+            });
+            ScVal::U32(case.with_context(|| format!("{name} has no case `{value}`"))?.value)
+        }
+        _ => bail!("{name} arguments are not supported"),
+    })
+}
+
+/// Splits arguments into words like a shell: quotes group words and backslashes escape the
+/// next character.
+pub fn split_words(args: &str) -> anyhow::Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = args.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some('"') | None, '\\') => {
+                let escaped = chars.next().context("trailing backslash")?;
+                word.get_or_insert_with(String::new).push(escaped);
+            }
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if let Some(open) = quote {
+        bail!("unclosed {open} quote");
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Splits text on `separator` outside double quotes, the parts keep their quotes. Backslashes
+/// escape the next character inside quotes.
+pub fn split_quoted(text: &str, separator: char) -> anyhow::Result<Vec<&str>> {
+    let mut parts = Vec::new();
+    let (mut start, mut quoted, mut escaped) = (0, false, false);
+    for (index, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                parts.push(&text[start..index]);
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    if quoted {
+        bail!("unclosed \" quote in `{text}`");
+    }
+    parts.push(&text[start..]);
+    Ok(parts)
+}
+
+fn integer<T: std::str::FromStr>(value: &Value) -> anyhow::Result<T> {
+    let text = match value {
+        Value::Number(number) if number.is_i64() || number.is_u64() => number.to_string(),
+        Value::Number(number) => bail!("`{number}` is not an integer, pass large numbers as strings"),
+        Value::String(text) => text.clone(),
+        other => bail!("expected a number, found `{other}`"),
+    };
+    text.parse().map_err(|_| anyhow!("invalid number `{text}`"))
+}
+
+/// Text of a string-like value, numbers and bools are taken as written.
+fn text(value: &Value) -> anyhow::Result<String> {
+    match value {
+        Value::String(text) => Ok(text.clone()),
+        Value::Number(_) | Value::Bool(_) => Ok(value.to_string()),
+        other => bail!("expected a string, found `{other}`"),
+    }
+}
+
+fn array<'a>(value: &'a Value, what: &str) -> anyhow::Result<&'a [Value]> {
+    match value {
+        Value::Array(elements) => Ok(elements),
+        other => bail!("expected a JSON array for {what}, found `{other}`"),
+    }
+}
+
+fn symbol(text: &str) -> anyhow::Result<ScVal> {
+    if !text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        bail!("invalid symbol `{text}`, symbols only hold letters, digits and `_`");
+    }
+    Ok(ScVal::Symbol(ScSymbol(text.try_into().map_err(|_| anyhow!("symbol `{text}` is too long"))?)))
+}
+
+/// Addresses are strkeys or CLI identities, resolved to their address.
+fn address(text: &str) -> anyhow::Result<ScVal> {
+    if let Ok(address) = ledger_key::parse_address(text) {
+        return Ok(ScVal::Address(address));
+    }
+    Ok(ScVal::Address(ledger_key::parse_address(&account::resolve(text)?)?))
+}
+
+/// Maps are sorted by key, the host rejects them otherwise.
+fn sorted_map(mut entries: Vec<ScMapEntry>) -> anyhow::Result<ScVal> {
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    if entries.windows(2).any(|pair| pair[0].key == pair[1].key) {
+        bail!("duplicate map key");
+    }
+    Ok(ScVal::Map(Some(ScMap(entries.try_into()?))))
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_xdr::curr::{
+        ScSpecFunctionInputV0, ScSpecTypeBytesN, ScSpecTypeMap, ScSpecTypeOption, ScSpecTypeUdt, ScSpecTypeVec,
+        ScSpecUdtEnumCaseV0, ScSpecUdtEnumV0, ScSpecUdtStructFieldV0, ScSpecUdtStructV0, ScSpecUdtUnionCaseTupleV0,
+        ScSpecUdtUnionCaseVoidV0, ScSpecUdtUnionV0,
+    };
+
+    use super::*;
+
+    const ACCOUNT: &str = "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7";
+
+    fn sym(text: &str) -> ScVal {
+        symbol(text).unwrap()
+    }
+
+    fn string(text: &str) -> ScVal {
+        ScVal::String(ScString(text.try_into().unwrap()))
+    }
+
+    fn vec(elements: Vec<ScVal>) -> ScVal {
+        ScVal::Vec(Some(ScVec(elements.try_into().unwrap())))
+    }
+
+    fn udt(name: &str) -> ScSpecTypeDef {
+        ScSpecTypeDef::Udt(ScSpecTypeUdt { name: name.try_into().unwrap() })
+    }
+
+    fn function(inputs: Vec<(&str, ScSpecTypeDef)>) -> ScSpecFunctionV0 {
+        ScSpecFunctionV0 {
+            name: ScSymbol("call".try_into().unwrap()),
+            inputs: inputs
+                .into_iter()
+                .map(|(name, type_)| ScSpecFunctionInputV0 { name: name.try_into().unwrap(), type_, ..Default::default() })
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+            ..Default::default()
+        }
+    }
+
+    fn types() -> Vec<ScSpecEntry> {
+        vec![
+            ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+                name: "Config".try_into().unwrap(),
+                fields: vec![
+                    ScSpecUdtStructFieldV0 { name: "limit".try_into().unwrap(), type_: ScSpecTypeDef::U32, ..Default::default() },
+                    ScSpecUdtStructFieldV0 { name: "admin".try_into().unwrap(), type_: ScSpecTypeDef::Address, ..Default::default() },
+                ]
+                .try_into()
+                .unwrap(),
+                ..Default::default()
+            }),
+            ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+                name: "DataKey".try_into().unwrap(),
+                cases: vec![
+                    ScSpecUdtUnionCaseV0::VoidV0(ScSpecUdtUnionCaseVoidV0 { name: "Admin".try_into().unwrap(), ..Default::default() }),
+                    ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                        name: "Balance".try_into().unwrap(),
+                        type_: vec![ScSpecTypeDef::Address].try_into().unwrap(),
+                        ..Default::default()
+                    }),
+                    ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                        name: "Allowance".try_into().unwrap(),
+                        type_: vec![ScSpecTypeDef::Address, ScSpecTypeDef::U32].try_into().unwrap(),
+                        ..Default::default()
+                    }),
+                ]
+                .try_into()
+                .unwrap(),
+                ..Default::default()
+            }),
+            ScSpecEntry::UdtEnumV0(ScSpecUdtEnumV0 {
+                name: "Level".try_into().unwrap(),
+                cases: vec![ScSpecUdtEnumCaseV0 { name: "High".try_into().unwrap(), value: 2, ..Default::default() }]
+                    .try_into()
+                    .unwrap(),
+                ..Default::default()
+            }),
+        ]
+    }
+
+    fn address() -> ScVal {
+        ScVal::Address(ledger_key::parse_address(ACCOUNT).unwrap())
+    }
+
+    #[test]
+    fn splits_words_like_a_shell() {
+        assert_eq!(
+            split_words(r#"--a "hello world" --b '["x y", 1]' --c=a\ b"#).unwrap(),
+            ["--a", "hello world", "--b", r#"["x y", 1]"#, "--c=a b"]
+        );
+        assert_eq!(split_words(r#"--a """#).unwrap(), ["--a", ""]);
+        assert!(split_words("--a 'open").is_err());
+    }
+
+    #[test]
+    fn parses_plain_values() {
+        let function = function(vec![
+            ("memo", ScSpecTypeDef::String),
+            ("amount", ScSpecTypeDef::I128),
+            ("to", ScSpecTypeDef::Address),
+            ("note", ScSpecTypeDef::Option(Box::new(ScSpecTypeOption { value_type: Box::new(ScSpecTypeDef::String) }))),
+            ("dry_run", ScSpecTypeDef::Bool),
+            ("hash", ScSpecTypeDef::BytesN(ScSpecTypeBytesN { n: 2 })),
+        ]);
+        let args = format!("--memo 'rent, March' --amount 170141183460469231731687303715884105727 --to {ACCOUNT} --dry_run --hash 0xbeef");
+        assert_eq!(
+            parse_args(&[], &function, &args).unwrap(),
+            [
+                string("rent, March"),
+                ScVal::from(i128::MAX),
+                address(),
+                ScVal::Void,
+                ScVal::Bool(true),
+                ScVal::Bytes(vec![0xbe, 0xef].try_into().unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_json_containers() {
+        let function = function(vec![
+            ("names", ScSpecTypeDef::Vec(Box::new(ScSpecTypeVec { element_type: Box::new(ScSpecTypeDef::String) }))),
+            (
+                "limits",
+                ScSpecTypeDef::Map(Box::new(ScSpecTypeMap {
+                    key_type: Box::new(ScSpecTypeDef::Symbol),
+                    value_type: Box::new(ScSpecTypeDef::U32),
+                })),
+            ),
+        ]);
+        let values = parse_args(&[], &function, r#"--names '["a b", "c,d"]' --limits '{"max": 9, "min": "1"}'"#).unwrap();
+        assert_eq!(values[0], vec(vec![string("a b"), string("c,d")]));
+        let expected_map = vec![
+            ScMapEntry { key: sym("max"), val: ScVal::U32(9) },
+            ScMapEntry { key: sym("min"), val: ScVal::U32(1) },
+        ];
+        assert_eq!(values[1], ScVal::Map(Some(ScMap(expected_map.try_into().unwrap()))));
+    }
+
+    #[test]
+    fn parses_user_types() {
+        let spec = types();
+        let config = parse_json(&spec, &udt("Config"), &serde_json::json!({ "limit": 5, "admin": ACCOUNT })).unwrap();
+        let expected_config = vec![
+            ScMapEntry { key: sym("admin"), val: address() },
+            ScMapEntry { key: sym("limit"), val: ScVal::U32(5) },
+        ];
+        assert_eq!(config, ScVal::Map(Some(ScMap(expected_config.try_into().unwrap()))));
+
+        assert_eq!(parse_arg(&spec, &udt("DataKey"), "Admin").unwrap(), vec(vec![sym("Admin")]));
+        assert_eq!(
+            parse_arg(&spec, &udt("DataKey"), &format!(r#"{{"Balance": "{ACCOUNT}"}}"#)).unwrap(),
+            vec(vec![sym("Balance"), address()])
+        );
+        assert_eq!(
+            parse_arg(&spec, &udt("DataKey"), &format!(r#"{{"Allowance": ["{ACCOUNT}", 3]}}"#)).unwrap(),
+            vec(vec![sym("Allowance"), address(), ScVal::U32(3)])
+        );
+        assert!(parse_arg(&spec, &udt("DataKey"), "Balance").is_err());
+        assert!(parse_arg(&spec, &udt("DataKey"), "Owner").is_err());
+
+        assert_eq!(parse_arg(&spec, &udt("Level"), "2").unwrap(), ScVal::U32(2));
+        assert_eq!(parse_arg(&spec, &udt("Level"), "High").unwrap(), ScVal::U32(2));
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert!(parse_arg(&[], &ScSpecTypeDef::Symbol, "a b").is_err());
+        assert!(parse_arg(&[], &ScSpecTypeDef::U32, "-1").is_err());
+        assert!(parse_arg(&[], &ScSpecTypeDef::BytesN(ScSpecTypeBytesN { n: 32 }), "beef").is_err());
+        assert!(parse_json(&[], &ScSpecTypeDef::U128, &serde_json::json!(1e30)).is_err());
+        let function = function(vec![("amount", ScSpecTypeDef::I128)]);
+        assert!(parse_args(&[], &function, "").is_err());
+        assert!(parse_args(&[], &function, "--amount").is_err());
+        assert!(parse_args(&[], &function, "amount 5").is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use anyhow::{bail, Context};
+use ed25519_dalek::{Signer, SigningKey};
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{
+    AccountId, Asset, ContractExecutable, ContractIdPreimage, Hash, HashIdPreimage, HashIdPreimageSorobanAuthorization,
+    Limits, PublicKey, ScAddress, ScMap, ScMapEntry, ScSymbol, ScVal, SorobanAuthorizationEntry, SorobanAuthorizedFunction,
+    SorobanAuthorizedInvocation, SorobanCredentials, Uint256, WriteXdr,
+};
+
+use crate::commands::commands::execute;
+use crate::commands::commands::StellarCliCmdName::KeysShow;
+use crate::network::StellarNetwork;
+use crate::scval;
+
+/// Ledgers a signature made here stays valid for, about ten minutes.
+pub const SIGNATURE_VALIDITY_LEDGERS: u32 = 120;
+
+/// Renders each authorization entry as the tree of invocations its address authorizes.
+pub fn render(entries: &[SorobanAuthorizationEntry]) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(format!("Authorizations ({})", entries.len()).bold())];
+    if entries.is_empty() {
+        lines.push(Line::raw("The invocation needs no authorization"));
+    }
+    for (index, entry) in entries.iter().enumerate() {
+        let header = match &entry.credentials {
+            SorobanCredentials::SourceAccount => {
+                Line::from(format!("#{index} source account, covered by the transaction signature").cyan())
+            }
+            SorobanCredentials::Address(credentials) => {
+                let status = match (&credentials.signature, &credentials.address) {
+                    (ScVal::Void, ScAddress::Contract(_)) => "unsigned, checked by the contract's __check_auth".to_string(),
+                    (ScVal::Void, _) => "unsigned".to_string(),
+                    _ => format!("signed, valid until ledger {}", credentials.signature_expiration_ledger),
+                };
+                let text = format!("#{index} {} (nonce {}) {status}", credentials.address, credentials.nonce);
+                if credentials.signature == ScVal::Void {
+                    Line::from(text.yellow())
+                } else {
+                    Line::from(text.green())
+                }
+            }
+        };
+        lines.push(header);
+        push_invocation(&entry.root_invocation, 1, &mut lines);
+    }
+    lines
+}
+
+fn push_invocation(invocation: &SorobanAuthorizedInvocation, depth: usize, lines: &mut Vec<Line<'static>>) {
+    let marker = if depth == 1 { "*" } else { "└" };
+    lines.push(Line::raw(format!("{}{marker} {}", "  ".repeat(depth), describe_function(&invocation.function))));
+    for sub_invocation in invocation.sub_invocations.iter() {
+        push_invocation(sub_invocation, depth + 1, lines);
+    }
+}
+
+fn describe_function(function: &SorobanAuthorizedFunction) -> String {
+    let format_args = |args: &[ScVal]| args.iter().map(scval::format).collect::<Vec<_>>().join(", ");
+    match function {
+        SorobanAuthorizedFunction::ContractFn(call) => format!(
+            "{}.{}({})",
+            call.contract_address,
+            call.function_name.to_utf8_string_lossy(),
+            format_args(&call.args)
+        ),
+        SorobanAuthorizedFunction::CreateContractHostFn(create) => {
+            format!("create contract {}", describe_creation(&create.contract_id_preimage, &create.executable))
+        }
+        SorobanAuthorizedFunction::CreateContractV2HostFn(create) => format!(
+            "create contract {} with constructor({})",
+            describe_creation(&create.contract_id_preimage, &create.executable),
+            format_args(&create.constructor_args)
+        ),
+    }
+}
+
+fn describe_creation(preimage: &ContractIdPreimage, executable: &ContractExecutable) -> String {
+    let executable = match executable {
+        ContractExecutable::Wasm(hash) => format!("wasm {hash}"),
+        ContractExecutable::StellarAsset => "stellar asset".to_string(),
+    };
+    match preimage {
+        ContractIdPreimage::Address(from) => format!(
+            "of {executable} from {} salt {}",
+            from.address,
+            scval::hex(&from.salt.0)
+        ),
+        ContractIdPreimage::Asset(Asset::Native) => format!("of {executable} for native"),
+        ContractIdPreimage::Asset(Asset::CreditAlphanum4(asset)) => {
+            format!("of {executable} for {}:{}", asset.asset_code, asset.issuer)
+        }
+        ContractIdPreimage::Asset(Asset::CreditAlphanum12(asset)) => {
+            format!("of {executable} for {}:{}", asset.asset_code, asset.issuer)
+        }
+    }
+}
+
+/// Reads the secret key of a CLI identity, the key itself never reaches the output.
+pub fn signing_key(identity: &str) -> anyhow::Result<SigningKey> {
+    let res = execute(KeysShow(identity.to_string()));
+    if !res.success {
+        bail!("could not read the secret key of `{identity}`: {}", res.stderr);
+    }
+    let secret = stellar_strkey::ed25519::PrivateKey::from_string(res.result.trim())
+        .with_context(|| format!("`{identity}` has no ed25519 secret key"))?;
+    Ok(SigningKey::from_bytes(&secret.0))
+}
+
+/// Signs the entries authorized by the key's account, returns how many were signed.
+pub fn sign_entries(
+    entries: &mut [SorobanAuthorizationEntry],
+    key: &SigningKey,
+    network: StellarNetwork,
+    expiration_ledger: u32,
+) -> anyhow::Result<usize> {
+    let account = ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
+        key.verifying_key().to_bytes(),
+    ))));
+    let network_id = Hash(Sha256::digest(network.network_passphrase()).into());
+
+    let mut signed = 0;
+    for entry in entries.iter_mut() {
+        let SorobanCredentials::Address(credentials) = &mut entry.credentials else {
+            continue;
+        };
+        if credentials.address != account {
+            continue;
+        }
+        let preimage = HashIdPreimage::SorobanAuthorization(HashIdPreimageSorobanAuthorization {
+            network_id: network_id.clone(),
+            nonce: credentials.nonce,
+            signature_expiration_ledger: expiration_ledger,
+            invocation: entry.root_invocation.clone(),
+        });
+        let payload = Sha256::digest(preimage.to_xdr(Limits::none())?);
+        let signature = key.sign(&payload);
+
+        // Account signatures are a vector of `{public_key, signature}` maps.
+        let signature_map = ScMap(
+            vec![
+                ScMapEntry {
+                    key: ScVal::Symbol(ScSymbol("public_key".try_into()?)),
+                    val: ScVal::Bytes(key.verifying_key().to_bytes().to_vec().try_into()?),
+                },
+                ScMapEntry {
+                    key: ScVal::Symbol(ScSymbol("signature".try_into()?)),
+                    val: ScVal::Bytes(signature.to_bytes().to_vec().try_into()?),
+                },
+            ]
+            .try_into()?,
+        );
+        credentials.signature = ScVal::Vec(Some(vec![ScVal::Map(Some(signature_map))].try_into()?));
+        credentials.signature_expiration_ledger = expiration_ledger;
+        signed += 1;
+    }
+    Ok(signed)
+}
+
+/// Addresses whose entries still lack a signature.
+pub fn unsigned_addresses(entries: &[SorobanAuthorizationEntry]) -> Vec<String> {
+    let mut addresses: Vec<String> = Vec::new();
+    for entry in entries {
+        if let SorobanCredentials::Address(credentials) = &entry.credentials {
+            let address = credentials.address.to_string();
+            if credentials.signature == ScVal::Void && !addresses.contains(&address) {
+                addresses.push(address);
+            }
+        }
+    }
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signature, Verifier};
+    use stellar_xdr::curr::{InvokeContractArgs, SorobanAddressCredentials};
+
+    use super::*;
+
+    const TESTNET_PASSPHRASE: &str = "Test SDF Network ; September 2015";
+
+    fn account(key: &SigningKey) -> ScAddress {
+        ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key.verifying_key().to_bytes()))))
+    }
+
+    fn entry(address: ScAddress, nonce: i64) -> SorobanAuthorizationEntry {
+        SorobanAuthorizationEntry {
+            credentials: SorobanCredentials::Address(SorobanAddressCredentials {
+                address,
+                nonce,
+                signature_expiration_ledger: 0,
+                signature: ScVal::Void,
+            }),
+            root_invocation: SorobanAuthorizedInvocation {
+                function: SorobanAuthorizedFunction::ContractFn(InvokeContractArgs {
+                    contract_address: ScAddress::Contract(Hash([3; 32]).into()),
+                    function_name: ScSymbol("transfer".try_into().unwrap()),
+                    args: vec![ScVal::U32(7)].try_into().unwrap(),
+                }),
+                sub_invocations: Default::default(),
+            },
+        }
+    }
+
+    fn credentials(entry: &SorobanAuthorizationEntry) -> &SorobanAddressCredentials {
+        match &entry.credentials {
+            SorobanCredentials::Address(credentials) => credentials,
+            SorobanCredentials::SourceAccount => panic!("not address credentials"),
+        }
+    }
+
+    #[test]
+    fn signs_the_authorization_preimage() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let other = SigningKey::from_bytes(&[9; 32]);
+        let mut entries = [entry(account(&key), 42), entry(account(&other), 43)];
+
+        let signed = sign_entries(&mut entries, &key, StellarNetwork::Testnet, 1_000).unwrap();
+        assert_eq!(signed, 1);
+
+        let preimage = HashIdPreimage::SorobanAuthorization(HashIdPreimageSorobanAuthorization {
+            network_id: Hash(Sha256::digest(TESTNET_PASSPHRASE).into()),
+            nonce: 42,
+            signature_expiration_ledger: 1_000,
+            invocation: entries[0].root_invocation.clone(),
+        });
+        let payload = Sha256::digest(preimage.to_xdr(Limits::none()).unwrap());
+
+        let signed_credentials = credentials(&entries[0]);
+        assert_eq!(signed_credentials.signature_expiration_ledger, 1_000);
+        let ScVal::Vec(Some(signatures)) = &signed_credentials.signature else {
+            panic!("signature is not a vec");
+        };
+        let ScVal::Map(Some(map)) = &signatures[0] else {
+            panic!("signature entry is not a map");
+        };
+        let bytes = |name: &str| match &map.iter().find(|entry| entry.key == ScVal::Symbol(ScSymbol(name.try_into().unwrap()))) {
+            Some(ScMapEntry { val: ScVal::Bytes(bytes), .. }) => bytes.to_vec(),
+            _ => panic!("no `{name}` bytes"),
+        };
+        assert_eq!(bytes("public_key"), key.verifying_key().to_bytes());
+        let signature = Signature::from_slice(&bytes("signature")).unwrap();
+        key.verifying_key().verify(&payload, &signature).unwrap();
+
+        assert_eq!(credentials(&entries[1]).signature, ScVal::Void);
+    }
+
+    #[test]
+    fn unsigned_addresses_are_listed_once() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let other = SigningKey::from_bytes(&[9; 32]);
+        let entries = [entry(account(&key), 1), entry(account(&other), 2), entry(account(&key), 3)];
+        assert_eq!(unsigned_addresses(&entries), [account(&key).to_string(), account(&other).to_string()]);
+    }
+}
@@ -0,0 +1,69 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+
+pub const LANGUAGE_OPTIONS: [&str; 3] = ["typescript", "rust", "json"];
+
+/// Lines of the generated interface shown in the preview.
+const PREVIEW_LINES: usize = 80;
+
+/// Directories of a generated TypeScript package that aren't worth listing.
+const SKIPPED_DIRS: [&str; 2] = ["node_modules", "dist"];
+
+/// File the Rust and JSON generators' output is saved to.
+pub fn output_file(language: &str, output_dir: &str) -> PathBuf {
+    let extension = if language == "rust" { "rs" } else { "json" };
+    Path::new(output_dir).join(format!("bindings.{extension}"))
+}
+
+/// File holding the contract's client interface, shown in the preview.
+pub fn interface_file(language: &str, output_dir: &str) -> PathBuf {
+    match language {
+        "typescript" => Path::new(output_dir).join("src").join("index.ts"),
+        _ => output_file(language, output_dir),
+    }
+}
+
+/// Renders the files under `dir` as an indented tree with their sizes.
+pub fn file_tree(dir: &str) -> anyhow::Result<Vec<Line<'static>>> {
+    let mut lines = vec![Line::from(format!("{dir}/").bold())];
+    push_tree(Path::new(dir), 1, &mut lines)?;
+    Ok(lines)
+}
+
+fn push_tree(dir: &Path, depth: usize, lines: &mut Vec<Line<'static>>) -> anyhow::Result<()> {
+    let mut entries: Vec<_> = std::fs::read_dir(dir)
+        .with_context(|| format!("could not list {}", dir.display()))?
+        .filter_map(Result::ok)
+        .collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let indent = "  ".repeat(depth);
+        if entry.path().is_dir() {
+            lines.push(Line::from(format!("{indent}{name}/").cyan()));
+            if !SKIPPED_DIRS.contains(&name.as_str()) {
+                push_tree(&entry.path(), depth + 1, lines)?;
+            }
+        } else {
+            let size = entry.metadata().map(|metadata| metadata.len()).unwrap_or_default();
+            lines.push(Line::raw(format!("{indent}{name}  ({size} bytes)")));
+        }
+    }
+    Ok(())
+}
+
+/// First lines of a generated file.
+pub fn preview(path: &Path) -> anyhow::Result<Vec<Line<'static>>> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    let total = content.lines().count();
+    let mut lines = vec![Line::from(format!("{}", path.display()).bold())];
+    lines.extend(content.lines().take(PREVIEW_LINES).map(|line| Line::raw(line.to_string())));
+    if total > PREVIEW_LINES {
+        lines.push(Line::from(format!("… {} more lines", total - PREVIEW_LINES).dim()));
+    }
+    Ok(lines)
+}
@@ -0,0 +1,163 @@
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::event::{Event, UiUpdateContent, UiWidget};
+use crate::scval;
+
+/// Folder in the cargo target directory the build copies its wasm files to, only the files of
+/// the last build are kept there.
+const OUT_DIR: &str = "stellar-explorer";
+
+/// A wasm file produced by a build.
+#[derive(Clone, Debug)]
+pub struct Artifact {
+    pub path: String,
+    pub size: u64,
+    pub hash: String,
+}
+
+/// Directory the builds of `workspace` copy their wasm files to, in `CARGO_TARGET_DIR` when it's
+/// set.
+pub fn out_dir(workspace: &str) -> PathBuf {
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("target"));
+    Path::new(workspace).join(target_dir).join(OUT_DIR)
+}
+
+/// Wasm artifacts of the last build of `workspace`, sorted by path.
+pub fn artifacts(workspace: &str) -> Vec<Artifact> {
+    let Ok(entries) = std::fs::read_dir(out_dir(workspace)) else {
+        return Vec::new();
+    };
+    let mut artifacts: Vec<Artifact> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "wasm"))
+        .filter_map(|path| {
+            let code = std::fs::read(&path).ok()?;
+            Some(Artifact {
+                path: path.to_string_lossy().to_string(),
+                size: code.len() as u64,
+                hash: scval::hex(&Sha256::digest(&code)),
+            })
+        })
+        .collect();
+    artifacts.sort_by(|a, b| a.path.cmp(&b.path));
+    artifacts
+}
+
+/// Removes the wasm files of the previous build, so the out dir only lists what the next one
+/// writes.
+fn clear_artifacts(out_dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(out_dir)?;
+    for entry in std::fs::read_dir(out_dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "wasm") {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Styles a line of cargo output, highlighting errors and warnings.
+fn highlight(line: &str) -> Line<'static> {
+    let trimmed = line.trim_start();
+    let line = line.to_string();
+    if trimmed.starts_with("error") {
+        Line::from(line.red().bold())
+    } else if trimmed.starts_with("warning") {
+        Line::from(line.yellow())
+    } else if trimmed.starts_with("-->") {
+        Line::from(line.cyan())
+    } else if trimmed.starts_with("Finished") || trimmed.starts_with("Compiling") {
+        Line::from(line.green())
+    } else {
+        Line::raw(line)
+    }
+}
+
+/// Runs `stellar contract build` in `workspace`, streaming its output to the command output and
+/// listing the artifacts once it's done.
+///
+/// The command runs through `std::process` rather than xshell, which only returns the output
+/// once the command has exited.
+pub fn run(workspace: String, package: String, sender: UnboundedSender<Event>) {
+    tokio::task::spawn_blocking(move || {
+        let send = |line: String| send_line(&sender, line);
+
+        let out_dir = out_dir(&workspace);
+        if let Err(error) = clear_artifacts(&out_dir) {
+            send(format!("error: could not prepare {}: {error}", out_dir.display()));
+            return;
+        }
+        let manifest_path: PathBuf = Path::new(&workspace).join("Cargo.toml");
+        let mut command = Command::new("stellar");
+        command
+            .args(["contract", "build", "--manifest-path"])
+            .arg(&manifest_path)
+            .arg("--out-dir")
+            .arg(&out_dir)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if !package.is_empty() {
+            command.args(["--package", &package]);
+        }
+
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(error) => {
+                send(format!("error: could not run stellar contract build: {error}"));
+                return;
+            }
+        };
+        // Cargo reports progress and diagnostics on stderr, both pipes are read at once so neither
+        // fills up and blocks the build.
+        let stderr = child.stderr.take().map(|stderr| {
+            let sender = sender.clone();
+            std::thread::spawn(move || {
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    if !send_line(&sender, line) {
+                        return;
+                    }
+                }
+            })
+        });
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                send(line);
+            }
+        }
+        if let Some(stderr) = stderr {
+            let _ = stderr.join();
+        }
+
+        match child.wait() {
+            Ok(status) if status.success() => {
+                send(String::from("Finished building, artifacts:"));
+                let artifacts = artifacts(&workspace);
+                if artifacts.is_empty() {
+                    send(String::from("warning: the build wrote no wasm artifacts"));
+                }
+                for artifact in artifacts {
+                    send(format!("  {}  {} bytes  {}", artifact.path, artifact.size, artifact.hash));
+                }
+                send(String::from("Use `Deploy Build Artifact` to deploy or upgrade from one of them"));
+            }
+            Ok(status) => {
+                send(format!("error: build failed ({status})"));
+            }
+            Err(error) => {
+                send(format!("error: {error}"));
+            }
+        }
+    });
+}
+
+fn send_line(sender: &UnboundedSender<Event>, line: String) -> bool {
+    let update = UiUpdateContent::new(UiWidget::CmdOutput, String::from("Build"), highlight(&line));
+    sender.send(Event::UiUpdate(update)).is_ok()
+}
@@ -7,14 +7,223 @@ use crate::commands::commands::{CmdResponse, StellarCliCmd, StellarCliCmdName};
 pub mod commands {
     use std::time::Duration;
     use xshell::{cmd, Shell};
-    use StellarCliCmdName::{Env, NetworkToggle};
+    use StellarCliCmdName::{
+        AssetContractId, AssetDeploy, ContractBindings, ContractDeploy, ContractExtend, ContractFetch,
+        ContractInterface, ContractInvoke, ContractRead, ContractRestore, ContractUpload, Env, KeysAddress, KeysShow,
+        NetworkToggle, TxSend, TxSign,
+    };
     use crate::commands::commands::StellarCliCmdName::{ReadContractDataWasm, Version};
+    use crate::network::StellarNetwork;
 
+    /// Contract explored when no other contract has been selected.
+    pub const DEFAULT_CONTRACT_ID: &str = "CBQDHNBFBZYE4MKPWBSJOPIYLW4SFSXAXUTSXJN76GNKYVYPCKWC6QUK";
+
+    #[derive(Clone, Debug)]
     pub enum StellarCliCmdName {
         Version,
         Env,
         ReadContractDataWasm,
-        NetworkToggle
+        NetworkToggle(StellarNetwork),
+        ContractRead(ContractDataArgs),
+        ContractRestore(ContractDataArgs),
+        ContractExtend(ContractDataArgs, u32),
+        ContractInvoke(InvokeArgs),
+        /// `contract info interface` of a deployed contract, as base64 `ScSpecEntry`s.
+        ContractInterface(StellarNetwork, String),
+        /// `contract fetch` of a contract's wasm into the given file.
+        ContractFetch(StellarNetwork, String, String),
+        ContractUpload(DeployArgs),
+        ContractDeploy(DeployArgs),
+        ContractBindings(BindingsArgs),
+        /// `contract id asset`, the Stellar Asset Contract ID of a `CODE:ISSUER` or `native` asset.
+        AssetContractId(StellarNetwork, String),
+        /// `contract asset deploy` of an asset with the given source account, `--build-only` when set.
+        AssetDeploy(StellarNetwork, String, String, bool),
+        /// `keys address` of a CLI identity.
+        KeysAddress(String),
+        /// `keys show`, the secret key of a CLI identity. Its output must never be printed.
+        KeysShow(String),
+        /// `tx sign` of a base64 transaction envelope with the given identity.
+        TxSign(StellarNetwork, String, String),
+        /// `tx send` of a signed base64 transaction envelope.
+        TxSend(StellarNetwork, String),
+    }
+
+    /// Arguments addressing a contract data entry, shared by `contract read`, `restore` and `extend`.
+    ///
+    /// Without `key` or `key_xdr` the commands target the contract instance.
+    #[derive(Clone, Debug, Default)]
+    pub struct ContractDataArgs {
+        pub network: StellarNetwork,
+        pub contract_id: String,
+        pub durability: String,
+        /// Symbol key, passed as `--key`.
+        pub key: String,
+        /// Base64 `ScVal` key, passed as `--key-xdr`.
+        pub key_xdr: String,
+        /// Signer of restore and extend transactions, the CLI default identity when empty.
+        pub source_account: String,
+        /// Print the unsigned restore or extend transaction instead of submitting it.
+        pub build_only: bool,
+    }
+
+    impl ContractDataArgs {
+        pub fn is_instance(&self) -> bool {
+            self.key.is_empty() && self.key_xdr.is_empty()
+        }
+
+        fn cli_args(&self) -> Vec<String> {
+            let mut args = vec![
+                "--network".to_string(),
+                self.network.to_string(),
+                "--id".to_string(),
+                self.contract_id.clone(),
+            ];
+            if !self.is_instance() {
+                args.extend(["--durability".to_string(), self.durability.clone()]);
+            }
+            if !self.key_xdr.is_empty() {
+                args.extend(["--key-xdr".to_string(), self.key_xdr.clone()]);
+            } else if !self.key.is_empty() {
+                args.extend(["--key".to_string(), self.key.clone()]);
+            }
+            args
+        }
+
+        fn tx_args(&self) -> Vec<String> {
+            let mut args = self.cli_args();
+            if !self.source_account.is_empty() {
+                args.extend(["--source-account".to_string(), self.source_account.clone()]);
+            }
+            if self.build_only {
+                args.push("--build-only".to_string());
+            }
+            args
+        }
+    }
+
+    /// Arguments of a `contract invoke` call.
+    #[derive(Clone, Debug, Default)]
+    pub struct InvokeArgs {
+        pub network: StellarNetwork,
+        pub contract_id: String,
+        pub source_account: String,
+        pub function: String,
+        /// Function arguments as typed after `--`, e.g. `--to GABC... --memo "March rent"`.
+        ///
+        /// Split into words like a shell, see [`cli_words`].
+        pub args: String,
+        /// Print the unsigned transaction instead of submitting it.
+        pub build_only: bool,
+    }
+
+    /// Words of arguments typed after `--`, quoted values keep their spaces. Unbalanced quotes
+    /// fall back to splitting on whitespace and the CLI reports the bad value.
+    fn cli_words(args: &str) -> Vec<String> {
+        crate::arguments::split_words(args).unwrap_or_else(|_| args.split_whitespace().map(str::to_string).collect())
+    }
+
+    impl InvokeArgs {
+        fn cli_args(&self) -> Vec<String> {
+            let mut args = vec![
+                "--network".to_string(),
+                self.network.to_string(),
+                "--id".to_string(),
+                self.contract_id.clone(),
+            ];
+            if !self.source_account.is_empty() {
+                args.extend(["--source-account".to_string(), self.source_account.clone()]);
+            }
+            if self.build_only {
+                args.push("--build-only".to_string());
+            }
+            args.extend(["--".to_string(), self.function.clone()]);
+            args.extend(cli_words(&self.args));
+            args
+        }
+    }
+
+    /// Arguments of `contract upload` and `contract deploy`.
+    #[derive(Clone, Debug, Default)]
+    pub struct DeployArgs {
+        pub network: StellarNetwork,
+        pub source_account: String,
+        /// Path of the wasm file to upload.
+        pub wasm: String,
+        /// Hash of the uploaded wasm to deploy.
+        pub wasm_hash: String,
+        /// Hex salt, the CLI picks a random one when empty.
+        pub salt: String,
+        /// Name the CLI registers the new contract ID under.
+        pub alias: String,
+        /// Constructor arguments as typed after `--`, e.g. `--admin GABC... --name "My token"`.
+        ///
+        /// Split into words like a shell, see [`cli_words`].
+        pub constructor_args: String,
+        /// Print the unsigned transaction instead of submitting it.
+        pub build_only: bool,
+    }
+
+    impl DeployArgs {
+        fn tx_args(&self) -> Vec<String> {
+            let mut args = vec!["--network".to_string(), self.network.to_string()];
+            if !self.source_account.is_empty() {
+                args.extend(["--source-account".to_string(), self.source_account.clone()]);
+            }
+            if self.build_only {
+                args.push("--build-only".to_string());
+            }
+            args
+        }
+
+        fn deploy_args(&self) -> Vec<String> {
+            let mut args = self.tx_args();
+            args.extend(["--wasm-hash".to_string(), self.wasm_hash.clone()]);
+            if !self.salt.is_empty() {
+                args.extend(["--salt".to_string(), self.salt.clone()]);
+            }
+            if !self.alias.is_empty() {
+                args.extend(["--alias".to_string(), self.alias.clone()]);
+            }
+            if !self.constructor_args.is_empty() {
+                args.push("--".to_string());
+                args.extend(cli_words(&self.constructor_args));
+            }
+            args
+        }
+    }
+
+    /// Arguments of `contract bindings`.
+    #[derive(Clone, Debug, Default)]
+    pub struct BindingsArgs {
+        pub network: StellarNetwork,
+        /// `typescript`, `rust` or `json`.
+        pub language: String,
+        pub contract_id: String,
+        /// Wasm to generate from, the Rust and JSON generators can't read a deployed contract.
+        pub wasm: String,
+        /// Directory the TypeScript package is generated into.
+        pub output_dir: String,
+    }
+
+    impl BindingsArgs {
+        fn cli_args(&self) -> Vec<String> {
+            let mut args = vec![self.language.clone()];
+            if self.language == "typescript" {
+                args.extend([
+                    "--network".to_string(),
+                    self.network.to_string(),
+                    "--contract-id".to_string(),
+                    self.contract_id.clone(),
+                    "--output-dir".to_string(),
+                    self.output_dir.clone(),
+                    "--overwrite".to_string(),
+                ]);
+            } else {
+                args.extend(["--wasm".to_string(), self.wasm.clone()]);
+            }
+            args
+        }
     }
 
     impl StellarCliCmdName {
@@ -32,10 +241,95 @@ pub mod commands {
                     let options = "--output json --id CBQDHNBFBZYE4MKPWBSJOPIYLW4SFSXAXUTSXJN76GNKYVYPCKWC6QUK --wasm 26c495019afb7448f690a82d6e66d8fab1ad3fd3e7b4aec7d554209966c9d19d --durability persistent";
                     StellarCliCmd::new(Version, cmd!(get_shell(), "stellar contract read {options}"))
                 }
-                NetworkToggle => {
-                    StellarCliCmd::new(Version, cmd!(get_shell(), "stellar network use local"))
+                NetworkToggle(network) => {
+                    let network = network.to_string();
+                    StellarCliCmd::new(Version, cmd!(get_shell(), "stellar network use {network}"))
+                }
+                ContractRead(args) => {
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(),
+                                       cmd!(get_shell(), "stellar contract read --output json").args(args.cli_args()))
+                }
+                ContractRestore(args) => {
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(),
+                                       cmd!(get_shell(), "stellar contract restore").args(args.tx_args()))
+                }
+                ContractExtend(args, ledgers_to_extend) => {
+                    let ledgers_to_extend = ledgers_to_extend.to_string();
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(),
+                                       cmd!(get_shell(), "stellar contract extend --ledgers-to-extend {ledgers_to_extend}")
+                                           .args(args.tx_args()))
+                }
+                ContractInvoke(args) => {
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(),
+                                       cmd!(get_shell(), "stellar contract invoke").args(args.cli_args()))
                 }
+                ContractInterface(network, contract_id) => {
+                    let network = network.to_string();
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(),
+                                       cmd!(get_shell(), "stellar contract info interface --output xdr-base64-array --network {network} --id {contract_id}"))
+                }
+                ContractFetch(network, contract_id, out_file) => {
+                    let network = network.to_string();
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(),
+                                       cmd!(get_shell(), "stellar contract fetch --network {network} --id {contract_id} --out-file {out_file}"))
+                }
+                ContractUpload(args) => {
+                    let wasm = &args.wasm;
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(),
+                                       cmd!(get_shell(), "stellar contract upload --wasm {wasm}").args(args.tx_args()))
+                }
+                ContractDeploy(args) => {
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(),
+                                       cmd!(get_shell(), "stellar contract deploy").args(args.deploy_args()))
+                }
+                ContractBindings(args) => {
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(),
+                                       cmd!(get_shell(), "stellar contract bindings").args(args.cli_args()))
+                }
+                AssetContractId(network, asset) => {
+                    let network = network.to_string();
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(),
+                                       cmd!(get_shell(), "stellar contract id asset --network {network} --asset {asset}"))
+                }
+                AssetDeploy(network, asset, source_account, build_only) => {
+                    let network = network.to_string();
+                    let mut cmd = cmd!(get_shell(), "stellar contract asset deploy --network {network} --asset {asset}");
+                    if !source_account.is_empty() {
+                        cmd = cmd.arg("--source-account").arg(source_account);
+                    }
+                    if *build_only {
+                        cmd = cmd.arg("--build-only");
+                    }
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(), cmd)
+                }
+                KeysAddress(identity) => {
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(), cmd!(get_shell(), "stellar keys address {identity}"))
+                }
+                KeysShow(identity) => {
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(), cmd!(get_shell(), "stellar keys show {identity}"))
+                }
+                TxSign(network, identity, envelope) => {
+                    let network = network.to_string();
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(),
+                                       cmd!(get_shell(), "stellar tx sign --network {network} --sign-with-key {identity} {envelope}"))
+                }
+                TxSend(network, envelope) => {
+                    let network = network.to_string();
+                    StellarCliCmd::new(stellar_cli_cmd_name.clone(),
+                                       cmd!(get_shell(), "stellar tx send --network {network} {envelope}"))
+                }
+            }
+        }
 
+        /// How long the command may run, transactions need to wait for the ledger to close.
+        fn timeout(&self) -> Duration {
+            match self {
+                ContractRestore(_) | ContractExtend(_, _) | ContractInvoke(_) | ContractUpload(_) | ContractDeploy(_)
+                | ContractBindings(_) | AssetDeploy(_, _, _, _) | TxSend(_, _) => {
+                    Duration::from_secs(30)
+                }
+                ContractInterface(_, _) | ContractFetch(_, _, _) => Duration::from_secs(10),
+                _ => Duration::from_secs(3),
             }
         }
     }
@@ -56,12 +350,14 @@ pub mod commands {
     
     pub struct CmdResponse {
         pub raw_cmd: xshell::Cmd,
-        pub result: String
+        pub result: String,
+        pub stderr: String,
+        pub success: bool,
     }
 
     impl CmdResponse {
-        pub fn new(raw_cmd: xshell::Cmd, result: String) -> Self {
-            Self { raw_cmd, result }
+        pub fn new(raw_cmd: xshell::Cmd, result: String, stderr: String, success: bool) -> Self {
+            Self { raw_cmd, result, stderr, success }
         }
     }
 
@@ -69,15 +365,19 @@ pub mod commands {
         let cmd =  command_factory(&stellar_cli_cmd);
 
 
-        // Run the command with a timeout
+        // Run the command with a timeout, capturing stderr so it doesn't end up on the terminal
         let res = cmd.cmd_slug.clone()
-                        .timeout(Duration::from_secs(3))
-                        .read()
-                        .unwrap_or_else(|_e| {
-                            "".parse().unwrap()
-                        });
+                        .timeout(stellar_cli_cmd.timeout())
+                        .ignore_status()
+                        .output();
 
-        CmdResponse::new(cmd.cmd_slug, res)
+        match res {
+            Ok(output) => CmdResponse::new(cmd.cmd_slug,
+                                           String::from_utf8_lossy(&output.stdout).trim_end().to_string(),
+                                           String::from_utf8_lossy(&output.stderr).trim_end().to_string(),
+                                           output.status.success()),
+            Err(e) => CmdResponse::new(cmd.cmd_slug, String::new(), e.to_string(), false),
+        }
     }
 
     fn get_shell() -> Shell {
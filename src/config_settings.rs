@@ -0,0 +1,148 @@
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use stellar_xdr::curr::{ConfigSettingEntry, ConfigSettingId, LedgerEntryData, LedgerKey, LedgerKeyConfigSetting};
+
+use crate::network::StellarNetwork;
+use crate::rpc;
+
+/// Settings shown by the network config viewer, in display order.
+const VIEWER_SETTINGS: [ConfigSettingId; 13] = [
+    ConfigSettingId::StateArchival,
+    ConfigSettingId::ContractLedgerCostV0,
+    ConfigSettingId::ContractLedgerCostExtV0,
+    ConfigSettingId::ContractComputeV0,
+    ConfigSettingId::ContractBandwidthV0,
+    ConfigSettingId::ContractEventsV0,
+    ConfigSettingId::ContractHistoricalDataV0,
+    ConfigSettingId::ContractMaxSizeBytes,
+    ConfigSettingId::ContractDataKeySizeBytes,
+    ConfigSettingId::ContractDataEntrySizeBytes,
+    ConfigSettingId::ContractExecutionLanes,
+    ConfigSettingId::ContractParallelComputeV0,
+    ConfigSettingId::ScpTiming,
+];
+
+/// Fetches the `ConfigSetting` ledger entries with the given ids from `network`.
+pub fn fetch(network: StellarNetwork, ids: &[ConfigSettingId]) -> anyhow::Result<Vec<ConfigSettingEntry>> {
+    let keys: Vec<LedgerKey> = ids
+        .iter()
+        .map(|&config_setting_id| LedgerKey::ConfigSetting(LedgerKeyConfigSetting { config_setting_id }))
+        .collect();
+
+    Ok(rpc::get_ledger_entries(network, &keys)?
+        .entries
+        .into_iter()
+        .filter_map(|entry| match entry.data {
+            LedgerEntryData::ConfigSetting(setting) => Some(setting),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Fetches the Soroban limits and fee parameters of `network` and renders them as a table.
+pub fn render_table(network: StellarNetwork) -> anyhow::Result<Vec<Line<'static>>> {
+    let mut settings = fetch(network, &VIEWER_SETTINGS)?;
+    settings.sort_by_key(|setting| {
+        VIEWER_SETTINGS
+            .iter()
+            .position(|id| *id == setting.discriminant())
+            .unwrap_or(VIEWER_SETTINGS.len())
+    });
+
+    let mut lines = vec![
+        Line::from(format!("Network config settings on {network}").bold()),
+        Line::from(format!("{:<44}{:>24}", "Setting", "Value").bold()),
+    ];
+    for setting in &settings {
+        lines.push(Line::raw(""));
+        lines.push(Line::from(section_title(setting).to_string().cyan().bold()));
+        lines.extend(
+            rows(setting)
+                .into_iter()
+                .map(|(name, value)| Line::raw(format!("{name:<44}{value:>24}"))),
+        );
+    }
+    Ok(lines)
+}
+
+fn section_title(setting: &ConfigSettingEntry) -> &'static str {
+    match setting {
+        ConfigSettingEntry::StateArchival(_) => "State archival",
+        ConfigSettingEntry::ContractLedgerCostV0(_) => "Ledger access limits and fees",
+        ConfigSettingEntry::ContractLedgerCostExtV0(_) => "Ledger write fees",
+        ConfigSettingEntry::ContractComputeV0(_) => "Compute limits and fees",
+        ConfigSettingEntry::ContractBandwidthV0(_) => "Bandwidth limits and fees",
+        ConfigSettingEntry::ContractEventsV0(_) => "Contract events",
+        ConfigSettingEntry::ContractHistoricalDataV0(_) => "Historical data",
+        ConfigSettingEntry::ContractExecutionLanes(_) => "Execution lanes",
+        ConfigSettingEntry::ContractParallelComputeV0(_) => "Parallel compute",
+        ConfigSettingEntry::ScpTiming(_) => "SCP timing",
+        _ => "Contract size limits",
+    }
+}
+
+fn rows(setting: &ConfigSettingEntry) -> Vec<(&'static str, String)> {
+    match setting {
+        ConfigSettingEntry::StateArchival(archival) => vec![
+            ("Max entry TTL (ledgers)", archival.max_entry_ttl.to_string()),
+            ("Min persistent TTL (ledgers)", archival.min_persistent_ttl.to_string()),
+            ("Min temporary TTL (ledgers)", archival.min_temporary_ttl.to_string()),
+            ("Persistent rent rate denominator", archival.persistent_rent_rate_denominator.to_string()),
+            ("Temporary rent rate denominator", archival.temp_rent_rate_denominator.to_string()),
+            ("Max entries to archive", archival.max_entries_to_archive.to_string()),
+            ("Eviction scan size", archival.eviction_scan_size.to_string()),
+        ],
+        ConfigSettingEntry::ContractLedgerCostV0(cost) => vec![
+            ("Tx max disk read entries", cost.tx_max_disk_read_entries.to_string()),
+            ("Tx max disk read bytes", cost.tx_max_disk_read_bytes.to_string()),
+            ("Tx max write entries", cost.tx_max_write_ledger_entries.to_string()),
+            ("Tx max write bytes", cost.tx_max_write_bytes.to_string()),
+            ("Ledger max disk read entries", cost.ledger_max_disk_read_entries.to_string()),
+            ("Ledger max disk read bytes", cost.ledger_max_disk_read_bytes.to_string()),
+            ("Ledger max write entries", cost.ledger_max_write_ledger_entries.to_string()),
+            ("Ledger max write bytes", cost.ledger_max_write_bytes.to_string()),
+            ("Fee per disk read entry (stroops)", cost.fee_disk_read_ledger_entry.to_string()),
+            ("Fee per write entry (stroops)", cost.fee_write_ledger_entry.to_string()),
+            ("Fee per 1KB disk read (stroops)", cost.fee_disk_read1_kb.to_string()),
+            ("Soroban state target size (bytes)", cost.soroban_state_target_size_bytes.to_string()),
+            ("Rent fee per 1KB at low state size", cost.rent_fee1_kb_soroban_state_size_low.to_string()),
+            ("Rent fee per 1KB at high state size", cost.rent_fee1_kb_soroban_state_size_high.to_string()),
+            ("Rent fee growth factor", cost.soroban_state_rent_fee_growth_factor.to_string()),
+        ],
+        ConfigSettingEntry::ContractLedgerCostExtV0(cost_ext) => vec![
+            ("Tx max footprint entries", cost_ext.tx_max_footprint_entries.to_string()),
+            ("Fee per 1KB written (stroops)", cost_ext.fee_write1_kb.to_string()),
+        ],
+        ConfigSettingEntry::ContractComputeV0(compute) => vec![
+            ("Tx max instructions", compute.tx_max_instructions.to_string()),
+            ("Tx memory limit (bytes)", compute.tx_memory_limit.to_string()),
+            ("Ledger max instructions", compute.ledger_max_instructions.to_string()),
+            ("Fee per 10k instructions (stroops)", compute.fee_rate_per_instructions_increment.to_string()),
+        ],
+        ConfigSettingEntry::ContractBandwidthV0(bandwidth) => vec![
+            ("Tx max size (bytes)", bandwidth.tx_max_size_bytes.to_string()),
+            ("Ledger max txs size (bytes)", bandwidth.ledger_max_txs_size_bytes.to_string()),
+            ("Fee per 1KB tx size (stroops)", bandwidth.fee_tx_size1_kb.to_string()),
+        ],
+        ConfigSettingEntry::ContractEventsV0(events) => vec![
+            ("Tx max events size (bytes)", events.tx_max_contract_events_size_bytes.to_string()),
+            ("Fee per 1KB events (stroops)", events.fee_contract_events1_kb.to_string()),
+        ],
+        ConfigSettingEntry::ContractHistoricalDataV0(historical) => vec![
+            ("Fee per 1KB historical data (stroops)", historical.fee_historical1_kb.to_string()),
+        ],
+        ConfigSettingEntry::ContractMaxSizeBytes(size) => vec![("Max contract size (bytes)", size.to_string())],
+        ConfigSettingEntry::ContractDataKeySizeBytes(size) => vec![("Max data key size (bytes)", size.to_string())],
+        ConfigSettingEntry::ContractDataEntrySizeBytes(size) => vec![("Max data entry size (bytes)", size.to_string())],
+        ConfigSettingEntry::ContractExecutionLanes(lanes) => vec![
+            ("Ledger max tx count", lanes.ledger_max_tx_count.to_string()),
+        ],
+        ConfigSettingEntry::ContractParallelComputeV0(parallel) => vec![
+            ("Ledger max dependent tx clusters", parallel.ledger_max_dependent_tx_clusters.to_string()),
+        ],
+        ConfigSettingEntry::ScpTiming(timing) => vec![
+            ("Ledger target close time (ms)", timing.ledger_target_close_time_milliseconds.to_string()),
+        ],
+        _ => vec![],
+    }
+}
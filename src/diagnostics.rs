@@ -0,0 +1,345 @@
+use anyhow::bail;
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span};
+use serde_json::{json, Value};
+use stellar_xdr::curr::{
+    ContractEventBody, ContractId, DiagnosticEvent, Hash, Limits, ReadXdr, ScError, ScSpecEntry,
+    ScSpecFunctionInputV0, ScVal,
+};
+
+use crate::commands::commands::StellarCliCmdName::ContractInvoke;
+use crate::commands::commands::{execute, InvokeArgs};
+use crate::rpc;
+use crate::{scval, spec};
+
+/// A contract call reconstructed from the `fn_call` and `fn_return` diagnostic events.
+#[derive(Clone, Debug)]
+struct Call {
+    contract: String,
+    function: String,
+    args: Vec<ScVal>,
+    depth: usize,
+    returned: bool,
+}
+
+/// Call stack and error decoded from the diagnostic events of a failed invocation.
+#[derive(Clone, Debug, Default)]
+struct Trace {
+    calls: Vec<Call>,
+    /// Index in `calls` of the innermost call that was running when the first error was raised.
+    failing_call: Option<usize>,
+    error: Option<ScError>,
+    /// Host message attached to the first error event.
+    message: String,
+}
+
+impl Trace {
+    fn from_events(events: &[DiagnosticEvent]) -> Self {
+        let mut trace = Trace::default();
+        let mut stack: Vec<usize> = Vec::new();
+
+        for event in events {
+            let ContractEventBody::V0(body) = &event.event.body;
+            let topic = body.topics.first().map(scval::format).unwrap_or_default();
+            match topic.as_str() {
+                "fn_call" => {
+                    let contract = body.topics.get(1).map(format_contract).unwrap_or_default();
+                    let function = body.topics.get(2).map(scval::format).unwrap_or_default();
+                    let args = match &body.data {
+                        ScVal::Vec(Some(args)) => args.to_vec(),
+                        ScVal::Void => Vec::new(),
+                        arg => vec![arg.clone()],
+                    };
+                    stack.push(trace.calls.len());
+                    trace.calls.push(Call { contract, function, args, depth: stack.len() - 1, returned: false });
+                }
+                "fn_return" => {
+                    if let Some(index) = stack.pop() {
+                        trace.calls[index].returned = true;
+                    }
+                }
+                "error" if trace.error.is_none() => {
+                    trace.error = body.topics.iter().find_map(|topic| match topic {
+                        ScVal::Error(error) => Some(error.clone()),
+                        _ => None,
+                    });
+                    trace.message = match &body.data {
+                        ScVal::String(message) => message.to_utf8_string_lossy(),
+                        ScVal::Vec(Some(values)) => values.iter().map(scval::format).collect::<Vec<_>>().join(", "),
+                        data => scval::format(data),
+                    };
+                    trace.failing_call = stack.last().copied();
+                }
+                _ => {}
+            }
+        }
+        trace
+    }
+}
+
+/// Explains why an invocation failed: the error, the contract error case it maps to, the call
+/// stack and the arguments of the failing call.
+///
+/// The invocation is simulated again through the RPC to get its diagnostic events decoded,
+/// falling back to the error in the CLI output when that's not possible.
+pub fn explain(args: &InvokeArgs, stderr: &str) -> anyhow::Result<Vec<Line<'static>>> {
+    let mut lines = vec![
+        Line::raw(""),
+        Line::from(format!("Why {} failed", args.function).red().bold()),
+    ];
+
+    let (simulation_error, events) = match simulate(args) {
+        Ok(simulation) => simulation,
+        Err(error) => {
+            lines.push(Line::from(format!("Could not simulate the invocation again: {error:#}").dim()));
+            (String::new(), Vec::new())
+        }
+    };
+    let trace = Trace::from_events(&events);
+    let spec = spec::fetch(args.network, &args.contract_id).unwrap_or_else(|error| {
+        lines.push(Line::from(format!("Contract spec unavailable: {error:#}").dim()));
+        Vec::new()
+    });
+
+    match &trace.error {
+        Some(error) => lines.extend(error_lines(error, &spec)),
+        None => {
+            let text = if simulation_error.is_empty() { stderr } else { &simulation_error };
+            let error = find_error_text(text).unwrap_or("unknown error, see the output above");
+            lines.push(Line::from(format!("Error: {error}").red()));
+        }
+    }
+    if !trace.message.is_empty() {
+        lines.push(Line::raw(format!("Host message: {}", trace.message)));
+    }
+
+    if !trace.calls.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::from("Call stack".bold()));
+        for (index, call) in trace.calls.iter().enumerate() {
+            lines.push(call_line(call, trace.failing_call == Some(index)));
+        }
+    }
+
+    if let Some(call) = trace.failing_call.map(|index| &trace.calls[index]) {
+        lines.push(Line::raw(""));
+        lines.push(Line::from(format!("Failing call: {}", call.function).red().bold()));
+        // Argument names are only known for functions of the invoked contract.
+        let inputs = spec::function(&spec, &call.function)
+            .filter(|_| call.contract == args.contract_id)
+            .map(|function| function.inputs.to_vec())
+            .unwrap_or_default();
+        lines.extend(argument_lines(call, &inputs, &trace.message));
+    }
+    Ok(lines)
+}
+
+/// Lists the arguments of a call, named after the spec's inputs when known, and marks those the
+/// error message mentions.
+fn argument_lines(call: &Call, inputs: &[ScSpecFunctionInputV0], message: &str) -> Vec<Line<'static>> {
+    call.args
+        .iter()
+        .enumerate()
+        .map(|(position, arg)| {
+            let value = scval::format(arg);
+            let label = match inputs.get(position) {
+                Some(input) => format!("{}: {}", input.name.to_utf8_string_lossy(), spec::type_name(&input.type_)),
+                None => format!("arg {position}"),
+            };
+            let line = format!("  {label} = {value}");
+            if message.contains(&value) {
+                Line::from(format!("{line}  <- in the error").red().bold())
+            } else {
+                Line::raw(line)
+            }
+        })
+        .collect()
+}
+
+/// Builds the invocation without sending it and simulates it, returning the simulation error
+/// and its diagnostic events.
+fn simulate(args: &InvokeArgs) -> anyhow::Result<(String, Vec<DiagnosticEvent>)> {
+    let res = execute(ContractInvoke(InvokeArgs {
+        build_only: true,
+        ..args.clone()
+    }));
+    if !res.success {
+        bail!("{}", res.stderr);
+    }
+    let result = rpc::request(args.network, "simulateTransaction", json!({ "transaction": res.result.trim() }))?;
+    let events = result["events"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Value::as_str)
+        .map(|xdr| Ok(DiagnosticEvent::from_xdr_base64(xdr, Limits::none())?))
+        .collect::<anyhow::Result<_>>()?;
+    Ok((result["error"].as_str().unwrap_or_default().to_string(), events))
+}
+
+fn error_lines(error: &ScError, spec: &[ScSpecEntry]) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(format!("Error: {}", scval::format_error(error)).red().bold())];
+    match error {
+        ScError::Contract(code) => {
+            let cases = spec::error_cases(spec, *code);
+            if cases.is_empty() {
+                lines.push(Line::raw(format!("Contract error #{code} is not defined in the contract spec")));
+            }
+            for (enum_name, case) in cases {
+                let doc = case.doc.to_utf8_string_lossy();
+                let mut line = format!("Contract error: {enum_name}::{} = {code}", case.name.to_utf8_string_lossy());
+                if !doc.is_empty() {
+                    line.push_str(&format!(" ({doc})"));
+                }
+                lines.push(Line::from(line.yellow().bold()));
+            }
+        }
+        _ => lines.push(Line::raw(format!("Host error of type {} raised by the environment", error.name()))),
+    }
+    lines
+}
+
+fn call_line(call: &Call, failing: bool) -> Line<'static> {
+    let args: Vec<String> = call.args.iter().map(scval::format).collect();
+    let text = format!(
+        "{}{} {}.{}({})",
+        "  ".repeat(call.depth),
+        if call.depth == 0 { "*" } else { "└" },
+        short_id(&call.contract),
+        call.function,
+        args.join(", ")
+    );
+    if failing {
+        Line::from(vec![Span::raw(text).red().bold(), Span::raw("  <- failed").red()])
+    } else if call.returned {
+        Line::from(text.green())
+    } else {
+        Line::from(text.yellow())
+    }
+}
+
+/// `fn_call` events name the called contract by its raw id.
+fn format_contract(topic: &ScVal) -> String {
+    match topic {
+        ScVal::Bytes(bytes) => match <[u8; 32]>::try_from(bytes.as_slice()) {
+            Ok(id) => ContractId(Hash(id)).to_string(),
+            Err(_) => scval::format(topic),
+        },
+        other => scval::format(other),
+    }
+}
+
+fn short_id(id: &str) -> String {
+    match (id.get(..4), id.get(id.len().saturating_sub(4)..)) {
+        (Some(start), Some(end)) if id.len() > 12 => format!("{start}…{end}"),
+        _ => id.to_string(),
+    }
+}
+
+/// Finds the `Error(Type, Code)` the CLI or the simulation reports.
+fn find_error_text(text: &str) -> Option<&str> {
+    let start = text.find("Error(")?;
+    let end = text[start..].find(')')?;
+    Some(&text[start..=start + end])
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_xdr::curr::{
+        ContractEvent, ContractEventType, ContractEventV0, ExtensionPoint, ScSpecTypeDef, ScString, ScSymbol, ScVec,
+    };
+
+    use super::*;
+
+    fn symbol(text: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(text.try_into().unwrap()))
+    }
+
+    fn event(topics: Vec<ScVal>, data: ScVal) -> DiagnosticEvent {
+        DiagnosticEvent {
+            in_successful_contract_call: false,
+            event: ContractEvent {
+                ext: ExtensionPoint::V0,
+                contract_id: None,
+                type_: ContractEventType::Diagnostic,
+                body: ContractEventBody::V0(ContractEventV0 { topics: topics.try_into().unwrap(), data }),
+            },
+        }
+    }
+
+    fn fn_call(contract: u8, function: &str, args: Vec<ScVal>) -> DiagnosticEvent {
+        let contract = ScVal::Bytes(vec![contract; 32].try_into().unwrap());
+        event(vec![symbol("fn_call"), contract, symbol(function)], ScVal::Vec(Some(ScVec(args.try_into().unwrap()))))
+    }
+
+    fn fn_return(function: &str) -> DiagnosticEvent {
+        event(vec![symbol("fn_return"), symbol(function)], ScVal::Void)
+    }
+
+    fn error(code: u32, message: &str, values: Vec<ScVal>) -> DiagnosticEvent {
+        let mut data = vec![ScVal::String(ScString(message.try_into().unwrap()))];
+        data.extend(values);
+        event(
+            vec![symbol("error"), ScVal::Error(ScError::Contract(code))],
+            ScVal::Vec(Some(ScVec(data.try_into().unwrap()))),
+        )
+    }
+
+    #[test]
+    fn failing_call_is_the_innermost_running_call() {
+        let trace = Trace::from_events(&[
+            fn_call(1, "swap", vec![ScVal::I64(5)]),
+            fn_call(2, "transfer", vec![ScVal::I64(7)]),
+            error(3, "insufficient balance", vec![]),
+            error(9, "later error", vec![]),
+        ]);
+        let calls: Vec<(&str, usize, bool)> =
+            trace.calls.iter().map(|call| (call.function.as_str(), call.depth, call.returned)).collect();
+        assert_eq!(calls, [("swap", 0, false), ("transfer", 1, false)]);
+        assert_eq!(trace.calls[1].contract, ContractId(Hash([2; 32])).to_string());
+        assert_eq!(trace.failing_call, Some(1));
+        assert_eq!(trace.error, Some(ScError::Contract(3)));
+        assert_eq!(trace.message, "\"insufficient balance\"");
+    }
+
+    #[test]
+    fn failure_after_an_inner_return_blames_the_caller() {
+        let trace = Trace::from_events(&[
+            fn_call(1, "swap", vec![]),
+            fn_call(2, "balance", vec![]),
+            fn_return("balance"),
+            fn_call(2, "transfer", vec![]),
+            fn_return("transfer"),
+            error(4, "slippage", vec![]),
+        ]);
+        let calls: Vec<(&str, usize, bool)> =
+            trace.calls.iter().map(|call| (call.function.as_str(), call.depth, call.returned)).collect();
+        assert_eq!(calls, [("swap", 0, false), ("balance", 1, true), ("transfer", 1, true)]);
+        assert_eq!(trace.failing_call, Some(0));
+    }
+
+    #[test]
+    fn no_error_event_leaves_no_failing_call() {
+        let trace = Trace::from_events(&[fn_call(1, "swap", vec![]), fn_return("swap")]);
+        assert_eq!((trace.failing_call, trace.error), (None, None));
+    }
+
+    #[test]
+    fn arguments_in_the_error_message_are_marked() {
+        let trace = Trace::from_events(&[
+            fn_call(1, "transfer", vec![ScVal::I64(100), ScVal::I64(250)]),
+            error(3, "amount exceeds balance", vec![ScVal::I64(250)]),
+        ]);
+        let inputs = [ScSpecFunctionInputV0 {
+            doc: Default::default(),
+            name: "balance".try_into().unwrap(),
+            type_: ScSpecTypeDef::I64,
+        }];
+        let lines: Vec<String> = argument_lines(&trace.calls[0], &inputs, &trace.message)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(lines, ["  balance: I64 = 100i64", "  arg 1 = 250i64  <- in the error"]);
+    }
+}
@@ -0,0 +1,178 @@
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use stellar_xdr::curr::{
+    DecoratedSignature, FeeBumpTransactionInnerTx, HostFunction, Limits, Memo, Operation, OperationBody, ReadXdr,
+    TransactionEnvelope, TransactionExt, TransactionV1Envelope,
+};
+use xshell::{cmd, Shell};
+
+use crate::network::StellarNetwork;
+use crate::simulation::{self, Simulation};
+use crate::scval;
+
+/// Clipboard tools tried in order, the first one installed receives the text.
+const CLIPBOARD_COMMANDS: [&[&str]; 4] = [
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+    &["pbcopy"],
+];
+
+/// Simulates a transaction printed by a `--build-only` command and returns it assembled,
+/// ready to be signed offline.
+pub fn assemble_unsigned(
+    network: StellarNetwork,
+    built_xdr: &str,
+) -> anyhow::Result<(TransactionEnvelope, Simulation)> {
+    let mut envelope = TransactionEnvelope::from_xdr_base64(built_xdr.trim(), Limits::none())
+        .context("the CLI did not print a transaction")?;
+    let simulated = simulation::simulate(network, &envelope)?;
+    simulation::assemble(&mut envelope, &simulated)?;
+    Ok((envelope, simulated))
+}
+
+/// Reads a base64 transaction envelope pasted as is or saved in a file.
+pub fn read(input: &str) -> anyhow::Result<TransactionEnvelope> {
+    let input = input.trim();
+    if input.is_empty() {
+        bail!("paste a transaction envelope or enter the file it was saved to");
+    }
+    let xdr = if Path::new(input).is_file() {
+        std::fs::read_to_string(input).with_context(|| format!("could not read {input}"))?
+    } else {
+        input.to_string()
+    };
+    TransactionEnvelope::from_xdr_base64(xdr.trim(), Limits::none()).context("not a base64 transaction envelope")
+}
+
+/// Renders a transaction envelope: its source, fees, operations and signatures.
+pub fn describe(envelope: &TransactionEnvelope) -> Vec<Line<'static>> {
+    match envelope {
+        TransactionEnvelope::TxV0(envelope) => {
+            let tx = &envelope.tx;
+            let mut lines = vec![
+                Line::raw(format!(
+                    "Source account: {}",
+                    stellar_strkey::ed25519::PublicKey(tx.source_account_ed25519.0)
+                )),
+                Line::raw(format!("Max fee: {} stroops", tx.fee)),
+                Line::raw(format!("Sequence number: {}, memo: {}", tx.seq_num.0, memo(&tx.memo))),
+            ];
+            lines.extend(describe_operations(&tx.operations));
+            lines.push(signatures_line(&envelope.signatures));
+            lines
+        }
+        TransactionEnvelope::Tx(envelope) => describe_v1(envelope),
+        TransactionEnvelope::TxFeeBump(envelope) => {
+            let FeeBumpTransactionInnerTx::Tx(inner) = &envelope.tx.inner_tx;
+            let mut lines = vec![
+                Line::raw(format!("Fee bump by {}, max fee: {} stroops", envelope.tx.fee_source, envelope.tx.fee)),
+                signatures_line(&envelope.signatures),
+                Line::from("Inner transaction".bold()),
+            ];
+            lines.extend(describe_v1(inner));
+            lines
+        }
+    }
+}
+
+fn describe_v1(envelope: &TransactionV1Envelope) -> Vec<Line<'static>> {
+    let tx = &envelope.tx;
+    let fee = match &tx.ext {
+        TransactionExt::V1(data) => format!(
+            "Max fee: {} stroops ({} inclusion, {} resources)",
+            tx.fee,
+            i64::from(tx.fee) - data.resource_fee,
+            data.resource_fee
+        ),
+        TransactionExt::V0 => format!("Max fee: {} stroops", tx.fee),
+    };
+    let mut lines = vec![
+        Line::raw(format!("Source account: {}", tx.source_account)),
+        Line::raw(fee),
+        Line::raw(format!("Sequence number: {}, memo: {}", tx.seq_num.0, memo(&tx.memo))),
+    ];
+    lines.extend(describe_operations(&tx.operations));
+    lines.push(signatures_line(&envelope.signatures));
+    lines
+}
+
+fn describe_operations(operations: &[Operation]) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(format!("Operations ({})", operations.len()).bold())];
+    for (index, operation) in operations.iter().enumerate() {
+        let detail = match &operation.body {
+            OperationBody::InvokeHostFunction(op) => {
+                let function = match &op.host_function {
+                    HostFunction::InvokeContract(call) => format!(
+                        "invoke {}.{}({})",
+                        call.contract_address,
+                        call.function_name.to_utf8_string_lossy(),
+                        call.args.iter().map(scval::format).collect::<Vec<_>>().join(", ")
+                    ),
+                    HostFunction::UploadContractWasm(wasm) => format!("upload {} bytes of wasm", wasm.len()),
+                    host_function => host_function.name().to_string(),
+                };
+                format!("{function}, {} authorizations", op.auth.len())
+            }
+            body => format!("{}: {}", body.name(), serde_json::to_string(body).unwrap_or_default()),
+        };
+        let source = operation
+            .source_account
+            .as_ref()
+            .map(|source| format!(" (source {source})"))
+            .unwrap_or_default();
+        lines.push(Line::raw(format!("  op {index}: {detail}{source}")));
+    }
+    lines
+}
+
+fn signatures_line(signatures: &[DecoratedSignature]) -> Line<'static> {
+    if signatures.is_empty() {
+        return Line::from("Not signed".red().bold());
+    }
+    let hints: Vec<String> = signatures.iter().map(|signature| scval::hex(&signature.hint.0)).collect();
+    Line::raw(format!("Signatures: {} (key hints {})", signatures.len(), hints.join(", ")))
+}
+
+fn memo(memo: &Memo) -> String {
+    match memo {
+        Memo::None => "none".to_string(),
+        Memo::Text(text) => format!("{:?}", text.to_utf8_string_lossy()),
+        Memo::Id(id) => id.to_string(),
+        Memo::Hash(hash) => format!("hash {hash}"),
+        Memo::Return(hash) => format!("return {hash}"),
+    }
+}
+
+pub fn save(path: &str, xdr: &str) -> anyhow::Result<()> {
+    std::fs::write(path, format!("{xdr}\n")).with_context(|| format!("could not write {path}"))
+}
+
+/// Copies text to the system clipboard, returns the tool that received it.
+///
+/// The tools' stderr is captured rather than written over the terminal UI, it's reported when
+/// none of them worked.
+pub fn copy(text: &str) -> anyhow::Result<&'static str> {
+    let sh = Shell::new()?;
+    let mut failures = Vec::new();
+    for command in CLIPBOARD_COMMANDS {
+        let Some((program, args)) = command.split_first() else {
+            continue;
+        };
+        // Tools that aren't installed fail to spawn and are skipped.
+        let Ok(output) = cmd!(sh, "{program}").args(args).stdin(text).ignore_status().output() else {
+            continue;
+        };
+        if output.status.success() {
+            return Ok(program);
+        }
+        failures.push(format!("{program}: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    if failures.is_empty() {
+        bail!("no clipboard tool found, install wl-copy, xclip or xsel");
+    }
+    bail!("could not copy the transaction, {}", failures.join(", "))
+}
@@ -8,6 +8,7 @@ use std::time::{Duration, SystemTime};
 
 use crossterm::event::{Event as CrosstermEvent, KeyEvent, MouseEvent};
 use futures::{FutureExt, pin_mut, SinkExt, StreamExt, TryFutureExt};
+use ratatui::text::Line;
 use tokio::sync::mpsc;
 use tokio::time::error::Elapsed;
 use tokio::time::Instant;
@@ -44,12 +45,13 @@ pub enum UiWidget {
 pub struct UiUpdateContent {
     ui_widget: UiWidget,
     ui_key: String,
-    ui_update_content: String,
+    /// Styled line to show, background tasks style it the way the one-shot views do.
+    ui_update_content: Line<'static>,
 }
 
 impl UiUpdateContent {
-    pub fn new(ui_widget: UiWidget, ui_key: String, ui_update_content: String) -> Self {
-        Self { ui_widget, ui_key, ui_update_content }
+    pub fn new(ui_widget: UiWidget, ui_key: String, ui_update_content: impl Into<Line<'static>>) -> Self {
+        Self { ui_widget, ui_key, ui_update_content: ui_update_content.into() }
     }
 }
 
@@ -60,7 +62,7 @@ impl UiUpdatePayload for UiUpdateContent {
     fn ui_key(&self) -> &str {
         &self.ui_key
     }
-    fn ui_update_content(&self) -> &str {
+    fn ui_update_content(&self) -> &Line<'static> {
         &self.ui_update_content
     }
 }
@@ -68,7 +70,7 @@ impl UiUpdatePayload for UiUpdateContent {
 pub trait UiUpdatePayload {
     fn ui_widget(&self) -> &UiWidget;
     fn ui_key(&self) -> &str;
-    fn ui_update_content(&self) -> &str;
+    fn ui_update_content(&self) -> &Line<'static>;
 }
 
 /// Terminal event handler.
@@ -148,6 +150,11 @@ impl EventHandler {
             )))
     }
 
+    /// Sender for background tasks reporting back to the UI.
+    pub fn sender(&self) -> mpsc::UnboundedSender<Event> {
+        self.sender.clone()
+    }
+
     pub fn send(&self, event: Event) -> AppResult<()> {
         self.sender.send(event).map(|e| { e })
             .unwrap_or_else(|e1| { e1; });
@@ -0,0 +1,269 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span};
+use serde_json::{json, Value};
+use stellar_xdr::curr::{ContractEvent, ContractEventBody, Limits, WriteXdr};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::event::{Event, UiUpdateContent, UiWidget};
+use crate::{arguments, ledger_key};
+use crate::network::StellarNetwork;
+use crate::rpc::{self, u32_field};
+use crate::scval;
+
+/// Event types accepted by the `getEvents` filter, `all` leaves the type out.
+pub const EVENT_TYPE_OPTIONS: [&str; 3] = ["all", "contract", "system"];
+
+/// How far back the events viewer looks when no start ledger is given.
+const DEFAULT_LEDGER_RANGE: u32 = 10_000;
+
+/// Follow mode polls about once per ledger close.
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A `getEvents` query for the events of one contract.
+#[derive(Clone, Debug, Default)]
+pub struct EventsQuery {
+    pub network: StellarNetwork,
+    pub contract_id: String,
+    pub start_ledger: Option<u32>,
+    pub end_ledger: Option<u32>,
+    pub event_type: String,
+    /// Base64 `ScVal` topic segments, or the `*` and `**` wildcards.
+    pub topics: Vec<String>,
+    pub limit: u32,
+    /// Continue after the last page instead of `start_ledger`.
+    pub cursor: Option<String>,
+}
+
+impl EventsQuery {
+    /// Moves the query past a fetched page, so following it doesn't return the same events
+    /// again. Pages without a cursor continue after the ledger of their last event.
+    pub fn advance(&mut self, page: &EventsPage) {
+        if page.cursor.is_some() {
+            self.cursor = page.cursor.clone();
+        } else if let Some(last_ledger) = page.last_ledger {
+            self.cursor = None;
+            self.start_ledger = Some(last_ledger + 1);
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct EventsPage {
+    pub lines: Vec<Line<'static>>,
+    pub cursor: Option<String>,
+    /// Ledger of the page's last event.
+    pub last_ledger: Option<u32>,
+    pub latest_ledger: u32,
+}
+
+/// Parses a topic filter into `getEvents` topic segments.
+///
+/// Segments are `*` (any topic), `**` (any remaining topics) or typed values like the data
+/// key builder accepts, e.g. `symbol:transfer, *, address:GABC...`. Untyped values are symbols.
+/// Segments containing commas are quoted, `"string:a, b"`, or the whole filter is a JSON array
+/// like vec keys, `["transfer", "*", "string:a, b"]`.
+pub fn parse_topic_filter(filter: &str) -> anyhow::Result<Vec<String>> {
+    let filter = filter.trim();
+    if filter.starts_with('[') {
+        let segments: Vec<Value> = serde_json::from_str(filter)
+            .with_context(|| format!("topic filters starting with `[` are JSON arrays, got `{filter}`"))?;
+        return segments
+            .iter()
+            .map(|segment| match segment {
+                Value::String(segment) => parse_topic(segment),
+                Value::Number(number) => Ok(ledger_key::parse_scval("u32", &number.to_string())?.to_xdr_base64(Limits::none())?),
+                other => bail!("unsupported topic `{other}`, use a `type:value` string"),
+            })
+            .collect();
+    }
+    arguments::split_quoted(filter, ',')?
+        .iter()
+        .map(|segment| segment.trim())
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| match segment.strip_prefix('"') {
+            Some(_) => {
+                let segment: String = serde_json::from_str(segment)
+                    .with_context(|| format!("quoted topics are JSON strings, got `{segment}`"))?;
+                parse_topic(&segment)
+            }
+            None => parse_topic(segment),
+        })
+        .collect()
+}
+
+fn parse_topic(segment: &str) -> anyhow::Result<String> {
+    let val = match segment.trim() {
+        "*" | "**" => return Ok(segment.trim().to_string()),
+        segment => match segment.split_once(':') {
+            Some((segment_type, value)) if ledger_key::KEY_TYPE_OPTIONS.contains(&segment_type) => {
+                ledger_key::parse_scval(segment_type, value)?
+            }
+            _ => ledger_key::parse_scval("symbol", segment)?,
+        },
+    };
+    Ok(val.to_xdr_base64(Limits::none())?)
+}
+
+pub fn latest_ledger(network: StellarNetwork) -> anyhow::Result<u32> {
+    let result = rpc::request(network, "getLatestLedger", json!({}))?;
+    match u32_field(&result, "sequence") {
+        Some(sequence) => Ok(sequence),
+        None => bail!("getLatestLedger returned no sequence"),
+    }
+}
+
+/// Fetches a page of events matching `query` and renders one line per event.
+pub fn fetch(query: &EventsQuery) -> anyhow::Result<EventsPage> {
+    let mut filter = json!({ "contractIds": [query.contract_id] });
+    if query.event_type != "all" && !query.event_type.is_empty() {
+        filter["type"] = json!(query.event_type);
+    }
+    if !query.topics.is_empty() {
+        filter["topics"] = json!([query.topics]);
+    }
+
+    let mut params = json!({
+        "filters": [filter],
+        "pagination": { "limit": query.limit },
+    });
+    match &query.cursor {
+        Some(cursor) => params["pagination"]["cursor"] = json!(cursor),
+        None => {
+            let start_ledger = match query.start_ledger {
+                Some(start_ledger) => start_ledger,
+                None => latest_ledger(query.network)?.saturating_sub(DEFAULT_LEDGER_RANGE).max(1),
+            };
+            params["startLedger"] = json!(start_ledger);
+            if let Some(end_ledger) = query.end_ledger {
+                params["endLedger"] = json!(end_ledger);
+            }
+        }
+    }
+
+    let result = rpc::request(query.network, "getEvents", params)?;
+    let events = result["events"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let lines = events.iter().map(render_event).collect();
+
+    Ok(EventsPage {
+        lines,
+        cursor: result["cursor"].as_str().map(str::to_string),
+        last_ledger: events.last().and_then(|event| u32_field(event, "ledger")),
+        latest_ledger: u32_field(&result, "latestLedger").unwrap_or_default(),
+    })
+}
+
+fn render_event(event: &Value) -> Line<'static> {
+    let topics: Vec<String> = event["topic"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Value::as_str)
+        .map(scval::format_xdr)
+        .collect();
+    let data = event["value"].as_str().map(scval::format_xdr).unwrap_or_default();
+    let tx_hash = event["txHash"].as_str().unwrap_or_default();
+
+    Line::from(vec![
+        Span::raw(format!("ledger {} ", u32_field(event, "ledger").unwrap_or_default())).dim(),
+        Span::raw(format!("{:<8} ", event["type"].as_str().unwrap_or_default())).cyan(),
+        Span::raw(format!("[{}] ", topics.join(", "))).bold(),
+        Span::raw(data),
+        Span::raw(format!("  tx {}", tx_hash.get(..12).unwrap_or(tx_hash))).dim(),
+    ])
+}
+
+/// Renders a decoded event from transaction meta, in the same layout as the events viewer.
+pub fn format_contract_event(event: &ContractEvent) -> Line<'static> {
+    let ContractEventBody::V0(body) = &event.body;
+    let topics: Vec<String> = body.topics.iter().map(scval::format).collect();
+    let contract = event
+        .contract_id
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+
+    Line::from(vec![
+        Span::raw(format!("{:<8} ", event.type_.name().to_lowercase())).cyan(),
+        Span::raw(format!("[{}] ", topics.join(", "))).bold(),
+        Span::raw(scval::format(&body.data)),
+        Span::raw(format!("  {contract}")).dim(),
+    ])
+}
+
+/// Polls for events after the last page every ledger close, appending them to the command
+/// output until `stop` is set.
+pub fn follow(mut query: EventsQuery, sender: UnboundedSender<Event>, stop: Arc<AtomicBool>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(FOLLOW_INTERVAL);
+        interval.tick().await;
+        while !stop.load(Ordering::Relaxed) {
+            interval.tick().await;
+            let page_query = query.clone();
+            let lines = match tokio::task::spawn_blocking(move || fetch(&page_query)).await {
+                Ok(Ok(page)) => {
+                    query.advance(&page);
+                    page.lines
+                }
+                Ok(Err(error)) => vec![Line::from(format!("Error: {error:#}").red())],
+                Err(_) => break,
+            };
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+            for line in lines {
+                let update = UiUpdateContent::new(UiWidget::CmdOutput, String::from("Events"), line);
+                if sender.send(Event::UiUpdate(update)).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_xdr::curr::{ReadXdr, ScVal};
+
+    use super::*;
+
+    fn decoded(segments: &[String]) -> Vec<String> {
+        segments
+            .iter()
+            .map(|segment| match segment.as_str() {
+                "*" | "**" => segment.clone(),
+                xdr => scval::format(&ScVal::from_xdr_base64(xdr, Limits::none()).unwrap()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn topic_values_keep_their_commas() {
+        let plain = decoded(&parse_topic_filter("transfer, *, \"string:a, b\", u32:7").unwrap());
+        let json = decoded(&parse_topic_filter("[\"transfer\", \"*\", \"string:a, b\", 7]").unwrap());
+        assert_eq!(plain, json);
+        assert_eq!(plain[1], "*");
+        assert_eq!(plain[0], scval::format(&ledger_key::parse_scval("symbol", "transfer").unwrap()));
+        assert_eq!(plain[2], scval::format(&ledger_key::parse_scval("string", "a, b").unwrap()));
+        assert!(parse_topic_filter("\"string:a, b").is_err());
+        assert!(parse_topic_filter("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn pages_without_a_cursor_continue_after_their_last_event() {
+        let mut query = EventsQuery { start_ledger: Some(100), cursor: Some("old".to_string()), ..Default::default() };
+        query.advance(&EventsPage { cursor: None, last_ledger: Some(120), ..Default::default() });
+        assert_eq!((query.start_ledger, query.cursor.as_deref()), (Some(121), None));
+
+        query.advance(&EventsPage::default());
+        assert_eq!((query.start_ledger, query.cursor.as_deref()), (Some(121), None));
+
+        query.advance(&EventsPage { cursor: Some("next".to_string()), last_ledger: Some(130), ..Default::default() });
+        assert_eq!(query.cursor.as_deref(), Some("next"));
+    }
+}
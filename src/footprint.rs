@@ -0,0 +1,53 @@
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use stellar_xdr::curr::{ContractDataDurability, LedgerKey, SorobanTransactionData, SorobanTransactionDataExt};
+
+use crate::archival::EntryStatus;
+use crate::ledger_entry;
+use crate::network::StellarNetwork;
+use crate::rpc;
+
+/// Lists the footprint of a simulated transaction entry by entry, with whether each entry is
+/// live, archived or missing on the ledger right now.
+pub fn render(network: StellarNetwork, data: &SorobanTransactionData) -> anyhow::Result<Vec<Line<'static>>> {
+    let footprint = &data.resources.footprint;
+    let keys: Vec<LedgerKey> = footprint.read_only.iter().chain(footprint.read_write.iter()).cloned().collect();
+    let ledger_entries = rpc::get_ledger_entries(network, &keys)?;
+    // Archived read-write entries the transaction restores itself, by footprint position.
+    let auto_restored: &[u32] = match &data.ext {
+        SorobanTransactionDataExt::V1(ext) => &ext.archived_soroban_entries,
+        SorobanTransactionDataExt::V0 => &[],
+    };
+
+    let mut lines = vec![Line::from(
+        format!(
+            "Footprint: {} read-only, {} read-write entries",
+            footprint.read_only.len(),
+            footprint.read_write.len()
+        )
+        .bold(),
+    )];
+    for (access, keys) in [("read-only ", &footprint.read_only), ("read-write", &footprint.read_write)] {
+        for (index, key) in keys.iter().enumerate() {
+            lines.push(Line::raw(format!("{access}  {}", ledger_entry::describe_key(key))));
+
+            let status = ledger_entries
+                .entries
+                .iter()
+                .find(|entry| entry.key == *key)
+                .map(|entry| EntryStatus::of(entry, ledger_entries.latest_ledger))
+                .unwrap_or(EntryStatus::NotFound);
+            let durability = match key {
+                LedgerKey::ContractData(data) => data.durability,
+                _ => ContractDataDurability::Persistent,
+            };
+            let mut status_line = status.to_line(durability);
+            status_line.spans.insert(0, "            ".into());
+            if access == "read-write" && auto_restored.contains(&(index as u32)) {
+                status_line.spans.push(", restored by this transaction".cyan());
+            }
+            lines.push(status_line);
+        }
+    }
+    Ok(lines)
+}
@@ -1,19 +1,36 @@
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
+use anyhow::{anyhow, bail};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use futures::{SinkExt, TryFutureExt};
-use ratatui::text::Line;
-use ratatui::widgets::ScrollDirection;
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Text};
 
-use StellarCliCmdName::{Env, ReadContractDataWasm, Version};
+use stellar_xdr::curr::{
+    ContractDataDurability, LedgerKey, Limits, ReadXdr, ScSpecTypeDef, TransactionEnvelope, WriteXdr,
+};
 
-use crate::app::{App, AppResult, ListStates};
-use crate::commands::commands::{CmdResponse, execute, StellarCliCmdName};
-use crate::commands::commands::StellarCliCmdName::NetworkToggle;
-use crate::event::{Event, EventHandler, UiUpdateContent, UiWidget};
+use crate::app::{App, AppResult, Form, FormField, FormKind, ListStates, MenuItem};
+use crate::commands::commands::{BindingsArgs, CmdResponse, ContractDataArgs, DeployArgs, InvokeArgs, execute};
+use crate::commands::commands::StellarCliCmdName::{
+    AssetDeploy, ContractBindings, ContractDeploy, ContractExtend, ContractFetch, ContractInvoke, ContractRead, ContractRestore, ContractUpload,
+    NetworkToggle, TxSend, TxSign,
+};
+use crate::event::EventHandler;
+use crate::profile::{self, CostSample};
+use crate::sandbox::{self, SandboxCall, Snapshot};
+use crate::simulation::Simulation;
+use crate::token::TokenSpec;
+use crate::events::{self, EventsQuery};
+use crate::ledger_key::{self, DURABILITY_OPTIONS};
+use crate::{account, archival, auth, bindings, build, config_settings, diagnostics, envelope, footprint, instance, ledger_entry, rent, sac, simulation, spec, storage, token, transaction, wasm};
 
 /// Handles the key events and updates the state of [`App`].
 pub fn handle_key_events(key_event: &KeyEvent, app: &mut App, event_handler: Arc<&EventHandler>) -> AppResult<()> {
+    if app.form.is_some() {
+        return handle_form_key_events(key_event, app, &event_handler);
+    }
+
     match key_event.code {
         // Exit application on `ESC` or `q`
         KeyCode::Esc | KeyCode::Char('q') => {
@@ -40,51 +57,1481 @@ pub fn handle_key_events(key_event: &KeyEvent, app: &mut App, event_handler: Arc
         }
 
         KeyCode::Tab => {
-            app.cmd_output_state.cmd_output_scrollbar.scroll(ScrollDirection::Forward);
+            app.scroll_output(true);
+        }
+        KeyCode::BackTab => {
+            app.scroll_output(false);
         }
 
         KeyCode::Delete => {
-            app.cmd_output_state.cmd_output.lines.clear();
+            app.clear_output();
         }
 
-        KeyCode::Enter => {
-            let stellar_cli_cmd_name: StellarCliCmdName = NetworkToggle;
-            // Run the command with a timeout
-            let res: CmdResponse = execute(NetworkToggle);
+        // Switch to the next network
+        KeyCode::Char('n') => {
+            app.network = app.network.next();
+            let res: CmdResponse = execute(NetworkToggle(app.network));
 
             app.cmd_output_state.cmd_output.push_line(Line::raw(res.raw_cmd.to_string()));
             app.cmd_output_state.cmd_output.push_line(Line::raw(res.result));
+            *app.cmd_output_state.network_status = network_status(app);
+        }
 
-            &event_handler.as_ref()
-                          .send(Event::UiUpdate(
-                              UiUpdateContent::new(UiWidget::CmdOutput,
-                                                   String::from("Result"),
-                                                   String::from("Network: Local"))))
-                          .unwrap_or_else(|e| { drop(e); });
-
-            match stellar_cli_cmd_name {
-                Version => {}
-                Env => {}
-                ReadContractDataWasm => {}
-                NetworkToggle => {
-                    &event_handler.as_ref()
-                                  .send(Event::UiUpdate(
-                                      UiUpdateContent::new(UiWidget::Network,
-                                                           String::from("Update"),
-                                                           String::from("Network: Local"))))
-                                  .unwrap_or_else(|e| { e; });
-
-                    &event_handler.as_ref()
-                                  .send(Event::UiUpdate(
-                                      UiUpdateContent::new(UiWidget::Network,
-                                                           String::from("Result"),
-                                                           String::from("Connect to local Stellar Network"))))
-                                  .unwrap_or_else(|e| { e; });
+        // Build transactions for offline signing instead of submitting them
+        KeyCode::Char('o') => {
+            app.build_only = !app.build_only;
+            *app.cmd_output_state.network_status = network_status(app);
+            let line = if app.build_only {
+                "Build-only mode: extend, restore, invoke and deploy print unsigned transactions".yellow().bold()
+            } else {
+                "Build-only mode off, transactions are submitted again".dim()
+            };
+            app.print_lines([Line::from(line)]);
+        }
+
+        // Save or copy the last unsigned transaction
+        KeyCode::Char('s') => {
+            if app.unsigned_tx.is_some() {
+                app.form = Some(Form::new(
+                    FormKind::SaveTransaction,
+                    vec![FormField::text("File", "transaction.xdr")],
+                ));
+            }
+        }
+        KeyCode::Char('y') => {
+            if let Some(xdr) = app.unsigned_tx.clone() {
+                match envelope::copy(&xdr) {
+                    Ok(tool) => app.print_lines([Line::from(format!("Copied the transaction with {tool}").green())]),
+                    Err(error) => app.print_error(error),
                 }
             }
         }
+
+        // Stop following contract events
+        KeyCode::Char('f') => {
+            if app.stop_following_events() {
+                app.print_lines([Line::from("Stopped following events".dim())]);
+            }
+        }
+
+        // Restore the archived entry found by the last read
+        KeyCode::Char('r') => {
+            if let Some(args) = app.pending_restore.take() {
+                app.form = Some(restore_form(&args));
+            }
+        }
+
+        // Drill into the browsed instance storage
+        KeyCode::Char('i') => {
+            if let Some(path) = app.instance_path.clone() {
+                let path = if path.is_empty() { path } else { format!("{path}/") };
+                app.form = Some(instance_form(&app.contract_id, &path));
+            }
+        }
+
+        // Refresh the inspected account
+        KeyCode::Char('a') => {
+            if let Some(account_id) = app.inspected_account.clone() {
+                let lines = account::render(app.network, &account_id);
+                app.print_result(lines);
+            }
+        }
+
+        KeyCode::Enter => {
+            if let Some(menu_item) = app.selected_menu_item() {
+                run_menu_item(menu_item, app, &event_handler)?;
+            }
+        }
         // Other handlers you could add here.
         _ => {}
     }
     Ok(())
 }
+
+/// Handles the key events while a [`Form`] is open.
+fn handle_form_key_events(key_event: &KeyEvent, app: &mut App, event_handler: &EventHandler) -> AppResult<()> {
+    let Some(form) = app.form.as_mut() else {
+        return Ok(());
+    };
+
+    match key_event.code {
+        KeyCode::Esc => {
+            app.form = None;
+        }
+        KeyCode::Char('c') | KeyCode::Char('C') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.quit();
+        }
+        KeyCode::Enter => {
+            if let Some(form) = app.form.take() {
+                submit_form(form, app, event_handler)?;
+            }
+        }
+        KeyCode::Down | KeyCode::Tab => {
+            form.next_field();
+        }
+        KeyCode::Up | KeyCode::BackTab => {
+            form.previous_field();
+        }
+        KeyCode::Left | KeyCode::Right => {
+            if let Some(field) = form.active_field_mut() {
+                field.cycle(key_event.code == KeyCode::Right);
+            }
+        }
+        KeyCode::Backspace => {
+            if let Some(field) = form.active_field_mut().filter(|field| !field.is_picker()) {
+                field.value.pop();
+            }
+        }
+        KeyCode::Char(c) => {
+            if let Some(field) = form.active_field_mut().filter(|field| !field.is_picker()) {
+                field.value.push(c);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Runs the script behind a menu item, opening its form if it needs input.
+fn run_menu_item(menu_item: MenuItem, app: &mut App, _event_handler: &EventHandler) -> AppResult<()> {
+    match menu_item {
+        MenuItem::RentFeeCalculator => {
+            app.form = Some(Form::new(
+                FormKind::RentFeeCalculator,
+                vec![
+                    FormField::text("Entry size (bytes)", "1024"),
+                    FormField::picker("Durability", &DURABILITY_OPTIONS),
+                    FormField::text("Extend by (ledgers)", "535680"),
+                ],
+            ));
+        }
+        MenuItem::ShowCostProfile => {
+            let lines = profile::render(&app.cost_samples);
+            app.print_lines(lines);
+        }
+        MenuItem::ShowNetworkConfig => {
+            let result = config_settings::render_table(app.network);
+            app.print_result(result);
+        }
+        MenuItem::ShowContractData => {
+            let args = target_args(app, false);
+            app.form = Some(Form::new(
+                FormKind::ReadContractData,
+                vec![
+                    FormField::text("Contract ID", &args.contract_id),
+                    FormField::picker("Durability", &DURABILITY_OPTIONS).with_value(&args.durability),
+                    FormField::text("Key (symbol)", &args.key),
+                    FormField::text("Key XDR", &args.key_xdr),
+                ],
+            ));
+        }
+        MenuItem::ExtendInstanceTtl | MenuItem::ExtendPersistenceTtl | MenuItem::ExtendTemporaryTtl => {
+            let mut args = target_args(app, menu_item == MenuItem::ExtendInstanceTtl);
+            if menu_item == MenuItem::ExtendTemporaryTtl {
+                args.durability = "temporary".to_string();
+            }
+            app.form = Some(Form::new(
+                FormKind::ExtendTtl,
+                vec![
+                    FormField::text("Contract ID", &args.contract_id),
+                    FormField::picker("Durability", &DURABILITY_OPTIONS).with_value(&args.durability),
+                    FormField::text("Key (symbol)", &args.key),
+                    FormField::text("Key XDR", &args.key_xdr),
+                    FormField::text("Extend by (ledgers)", "535680"),
+                    FormField::text("Source account", &args.source_account),
+                ],
+            ));
+        }
+        MenuItem::RestorePersistentStorage | MenuItem::RestoreContractInstance => {
+            let args = target_args(app, menu_item == MenuItem::RestoreContractInstance);
+            app.form = Some(restore_form(&args));
+        }
+        MenuItem::ExploreLedgerEntry => {
+            let args = target_args(app, true);
+            app.form = Some(Form::new(
+                FormKind::LedgerEntryExplorer,
+                vec![
+                    FormField::picker("Key kind", &ledger_key::LEDGER_KEY_KIND_OPTIONS),
+                    FormField::text("LedgerKey XDR", ""),
+                    FormField::text("Account ID", &app.source_account),
+                    FormField::text("Asset (CODE:ISSUER)", ""),
+                    FormField::text("Wasm hash", ""),
+                    FormField::text("Contract ID", &args.contract_id),
+                    FormField::picker("Durability", &DURABILITY_OPTIONS),
+                    FormField::picker("Key type", &ledger_key::KEY_TYPE_OPTIONS),
+                    FormField::text("Key value", ""),
+                ],
+            ));
+        }
+        MenuItem::ShowContractEvents => {
+            app.form = Some(Form::new(
+                FormKind::ContractEvents,
+                vec![
+                    FormField::text("Contract ID", &app.contract_id),
+                    FormField::text("Start ledger", ""),
+                    FormField::text("End ledger", ""),
+                    FormField::picker("Event type", &events::EVENT_TYPE_OPTIONS),
+                    FormField::text("Topic filter", ""),
+                    FormField::text("Limit", "100"),
+                    FormField::picker("Follow new events", &["no", "yes"]),
+                ],
+            ));
+        }
+        MenuItem::GenerateDataKey => {
+            let args = target_args(app, true);
+            app.form = Some(Form::new(
+                FormKind::GenerateDataKey,
+                vec![
+                    FormField::text("Contract ID", &args.contract_id),
+                    FormField::picker("Durability", &DURABILITY_OPTIONS).with_value(&args.durability),
+                    FormField::picker("Key type", &ledger_key::KEY_TYPE_OPTIONS),
+                    FormField::text("Key value", ""),
+                ],
+            ));
+        }
+        MenuItem::InvokeContract => {
+            app.form = Some(Form::new(
+                FormKind::InvokeContract,
+                vec![
+                    FormField::text("Contract ID", &app.contract_id),
+                    FormField::text("Function", ""),
+                    FormField::text("Arguments", ""),
+                    FormField::text("Source account", &app.source_account),
+                ],
+            ));
+        }
+        MenuItem::ReviewAuthorizations => {
+            app.form = Some(Form::new(
+                FormKind::ReviewAuthorizations,
+                vec![
+                    FormField::text("Contract ID", &app.contract_id),
+                    FormField::text("Function", ""),
+                    FormField::text("Arguments", ""),
+                    FormField::text("Source account", &app.source_account),
+                ],
+            ));
+        }
+        MenuItem::PreviewFootprint => {
+            app.form = Some(Form::new(
+                FormKind::PreviewFootprint,
+                vec![
+                    FormField::text("Contract ID", &app.contract_id),
+                    FormField::text("Function", ""),
+                    FormField::text("Arguments", ""),
+                    FormField::text("Source account", &app.source_account),
+                ],
+            ));
+        }
+        MenuItem::SubmitSignedTransaction => {
+            app.form = Some(Form::new(
+                FormKind::SubmitSignedTransaction,
+                vec![
+                    FormField::text("Signed envelope (XDR or file)", "transaction.xdr"),
+                    FormField::picker("Send", &["no", "yes"]),
+                ],
+            ));
+        }
+        MenuItem::BrowseInstanceStorage => {
+            app.form = Some(instance_form(&app.contract_id, ""));
+        }
+        MenuItem::SnapshotStorage => {
+            app.form = Some(Form::new(
+                FormKind::SnapshotStorage,
+                vec![
+                    FormField::text("Contract ID", &app.contract_id),
+                    FormField::text("File", "storage.json"),
+                ],
+            ));
+        }
+        MenuItem::DiffStorage => {
+            app.form = Some(Form::new(
+                FormKind::DiffStorage,
+                vec![
+                    FormField::text("Before", "storage.json"),
+                    FormField::text("After (file or live)", "live"),
+                    FormField::text("Contract ID", &app.contract_id),
+                ],
+            ));
+        }
+        MenuItem::CaptureSnapshot => {
+            app.form = Some(Form::new(
+                FormKind::CaptureSnapshot,
+                vec![
+                    FormField::text("Contract ID", &app.contract_id),
+                    FormField::text("Function", ""),
+                    FormField::text("Arguments", ""),
+                    FormField::text("Source account", &app.source_account),
+                    FormField::text("Snapshot file", &app.snapshot_path),
+                ],
+            ));
+        }
+        MenuItem::RunInSandbox => {
+            app.form = Some(Form::new(
+                FormKind::RunInSandbox,
+                vec![
+                    FormField::text("Snapshot file", &app.snapshot_path),
+                    FormField::text("Contract ID", &app.contract_id),
+                    FormField::text("Function", ""),
+                    FormField::text("Arguments", ""),
+                    FormField::text("Source account", &app.source_account),
+                    FormField::picker("Keep changes", &["no", "yes"]),
+                ],
+            ));
+        }
+        MenuItem::DeployContract => {
+            app.form = Some(Form::new(
+                FormKind::UploadWasm,
+                vec![
+                    FormField::text("Wasm file", &app.wasm_path),
+                    FormField::text("Source account", &app.source_account),
+                ],
+            ));
+        }
+        MenuItem::UpgradeContract => {
+            app.form = Some(Form::new(
+                FormKind::CompareUpgrade,
+                vec![
+                    FormField::text("Contract ID", &app.contract_id),
+                    FormField::text("Wasm file", &app.wasm_path),
+                ],
+            ));
+        }
+        MenuItem::InspectContractWasm => {
+            app.form = Some(Form::new(
+                FormKind::InspectWasm,
+                vec![
+                    FormField::picker("Source", &["contract", "file"]),
+                    FormField::text("Contract ID", &app.contract_id),
+                    FormField::text("Wasm file", ""),
+                ],
+            ));
+        }
+        MenuItem::BuildContract => {
+            app.form = Some(Form::new(
+                FormKind::BuildContract,
+                vec![
+                    FormField::text("Workspace", &app.workspace),
+                    FormField::text("Package", ""),
+                ],
+            ));
+        }
+        MenuItem::DeployArtifact => {
+            let artifacts: Vec<String> = build::artifacts(&app.workspace)
+                .into_iter()
+                .map(|artifact| artifact.path)
+                .collect();
+            if artifacts.is_empty() {
+                let out_dir = build::out_dir(&app.workspace);
+                app.print_lines([Line::from(format!("No wasm artifacts in {}, build the contract first", out_dir.display()).yellow())]);
+                return Ok(());
+            }
+            app.form = Some(Form::new(
+                FormKind::DeployArtifact,
+                vec![
+                    FormField::picker("Artifact", &artifacts),
+                    FormField::picker("Action", &["deploy", "upgrade"]),
+                ],
+            ));
+        }
+        MenuItem::GenerateBindings => {
+            app.form = Some(Form::new(
+                FormKind::GenerateBindings,
+                vec![
+                    FormField::text("Contract ID", &app.contract_id),
+                    FormField::picker("Language", &bindings::LANGUAGE_OPTIONS),
+                    FormField::text("Output directory", "bindings"),
+                ],
+            ));
+        }
+        MenuItem::ShowAssetContract => {
+            app.form = Some(Form::new(
+                FormKind::AssetContract,
+                vec![
+                    FormField::text("Asset (CODE:ISSUER or native)", "native"),
+                    FormField::text("Balances of (comma separated)", &app.source_account),
+                    FormField::picker("Deploy if missing", &["no", "yes"]),
+                    FormField::text("Source account", &app.source_account),
+                ],
+            ));
+        }
+        MenuItem::TokenDashboard => {
+            let token_spec = match cached_token_spec(app) {
+                Ok(token_spec) => token_spec,
+                Err(error) => {
+                    app.print_error(error);
+                    return Ok(());
+                }
+            };
+            let missing = token_spec.missing_functions();
+            if !missing.is_empty() {
+                app.print_lines([Line::from(format!(
+                    "{} does not implement the SEP-41 token interface, missing: {}",
+                    app.contract_id,
+                    missing.join(", ")
+                ).yellow())]);
+                return Ok(());
+            }
+            app.form = Some(Form::new(
+                FormKind::TokenDashboard,
+                vec![
+                    FormField::picker("Action", &token_spec.actions()),
+                    FormField::text("From / Address", &app.source_account),
+                    FormField::text("To / Spender", ""),
+                    FormField::text("Amount", "0"),
+                    FormField::text("Expiration ledger", "0"),
+                    FormField::text("Source account", &app.source_account),
+                ],
+            ));
+        }
+        MenuItem::InspectAccount => {
+            app.form = Some(Form::new(
+                FormKind::AccountInspector,
+                vec![FormField::text("Address or identity", &app.source_account)],
+            ));
+        }
+        MenuItem::FundAccount => {
+            let Some(default_url) = app.network.friendbot_url() else {
+                app.print_lines([Line::from(format!("There is no friendbot on {}", app.network).red())]);
+                return Ok(());
+            };
+            let friendbot_url = app.friendbot_urls.get(&app.network).map_or(default_url, String::as_str).to_string();
+            app.form = Some(Form::new(
+                FormKind::FundAccount,
+                vec![
+                    FormField::text("Address or identity", &app.source_account),
+                    FormField::text("Friendbot URL", &friendbot_url),
+                ],
+            ));
+        }
+        MenuItem::LookUpTransaction => {
+            app.form = Some(Form::new(
+                FormKind::TransactionLookup,
+                vec![FormField::text("Transaction hash", &app.tx_hash)],
+            ));
+        }
+        _ => {
+            app.print_lines([Line::from(format!("{menu_item} is not available yet").dim())]);
+        }
+    }
+    Ok(())
+}
+
+/// Runs the script a submitted [`Form`] was collecting input for.
+fn submit_form(form: Form, app: &mut App, event_handler: &EventHandler) -> AppResult<()> {
+    let result = match form.kind {
+        FormKind::RentFeeCalculator => calculate_rent(&form, app).map(|lines| app.print_lines(lines)),
+        FormKind::ReadContractData => read_contract_data(&form, app),
+        FormKind::ExtendTtl => extend_ttl(&form, app),
+        FormKind::RestoreEntry => restore_entry(&form, app),
+        FormKind::GenerateDataKey => generate_data_key(&form, app),
+        FormKind::LedgerEntryExplorer => explore_ledger_entry(&form, app),
+        FormKind::ContractEvents => show_contract_events(&form, app, event_handler),
+        FormKind::TransactionLookup => look_up_transaction(&form, app),
+        FormKind::InvokeContract => invoke_contract(&form, app),
+        FormKind::UploadWasm => upload_wasm(&form, app),
+        FormKind::DeployContract => deploy_contract(&form, app),
+        FormKind::CompareUpgrade => compare_upgrade(&form, app),
+        FormKind::ConfirmUpgrade => confirm_upgrade(&form, app),
+        FormKind::InspectWasm => inspect_wasm(&form, app),
+        FormKind::BuildContract => build_contract(&form, app, event_handler),
+        FormKind::DeployArtifact => deploy_artifact(&form, app, event_handler),
+        FormKind::GenerateBindings => generate_bindings(&form, app),
+        FormKind::AssetContract => show_asset_contract(&form, app),
+        FormKind::TokenDashboard => run_token_action(&form, app),
+        FormKind::AccountInspector => inspect_account(&form, app),
+        FormKind::FundAccount => fund_account(&form, app),
+        FormKind::ReviewAuthorizations => review_authorizations(&form, app),
+        FormKind::SignAuthorizations => sign_authorizations(&form, app),
+        FormKind::PreviewFootprint => preview_footprint(&form, app),
+        FormKind::SaveTransaction => save_transaction(&form, app),
+        FormKind::SubmitSignedTransaction => submit_signed_transaction(&form, app, event_handler),
+        FormKind::CaptureSnapshot => capture_snapshot(&form, app),
+        FormKind::BrowseInstanceStorage => browse_instance_storage(&form, app),
+        FormKind::SnapshotStorage => snapshot_storage(&form, app),
+        FormKind::DiffStorage => diff_storage(&form, app),
+        FormKind::RunInSandbox => run_in_sandbox(&form, app),
+    };
+    if let Err(error) = result {
+        app.print_error(error);
+    }
+    Ok(())
+}
+
+/// Network shown in the status widget, flagged while transactions aren't submitted.
+fn network_status(app: &App) -> Text<'static> {
+    if app.build_only {
+        Text::raw(format!("Network: {} (build only)", app.network))
+    } else {
+        Text::raw(format!("Network: {}", app.network))
+    }
+}
+
+/// Prints the transaction a `--build-only` command built, simulated and ready to be signed
+/// offline, and keeps it for saving with `s` and copying with `y`.
+fn print_unsigned(res: &CmdResponse, app: &mut App) -> anyhow::Result<()> {
+    if !res.success {
+        app.print_cmd_response(res);
+        return Ok(());
+    }
+    app.print_lines([Line::from(format!("$ {}", res.raw_cmd).dim())]);
+    let (envelope, simulated) = envelope::assemble_unsigned(app.network, &res.result)?;
+    record_simulated_cost(&envelope, &simulated, app);
+    print_footprint(&envelope, app);
+    let xdr = envelope.to_xdr_base64(Limits::none())?;
+    app.print_lines([
+        Line::from("Unsigned transaction, sign it offline and send it with `Submit Signed Transaction`".yellow().bold()),
+        Line::raw(xdr.clone()),
+        Line::from("Press `s` to save it to a file, `y` to copy it".dim()),
+    ]);
+    app.unsigned_tx = Some(xdr);
+    Ok(())
+}
+
+/// Lists the entries a Soroban transaction reads and writes, if it carries a footprint.
+fn print_footprint(envelope: &TransactionEnvelope, app: &mut App) {
+    if let Some(data) = simulation::transaction_data(envelope) {
+        let footprint = &data.resources.footprint;
+        remember_storage_keys(footprint.read_only.iter().chain(footprint.read_write.iter()), app);
+        let lines = footprint::render(app.network, data);
+        app.print_result(lines);
+    }
+}
+
+/// Keeps the contract data keys for later storage snapshots.
+fn remember_storage_keys<'a>(keys: impl Iterator<Item = &'a LedgerKey>, app: &mut App) {
+    for key in keys {
+        if matches!(key, LedgerKey::ContractData(_)) && !app.storage_keys.contains(key) {
+            app.storage_keys.push(key.clone());
+        }
+    }
+}
+
+fn record_simulated_cost(envelope: &TransactionEnvelope, simulated: &Simulation, app: &mut App) {
+    if let Some(sample) = CostSample::simulated(app.network, envelope, simulated) {
+        app.cost_samples.push(sample);
+    }
+}
+
+/// Records the costs of an invocation the CLI ran: from its transaction when it was submitted,
+/// or from a simulation of our own for read-only calls the CLI only simulated.
+fn record_invocation_cost(args: &InvokeArgs, res: &CmdResponse, app: &mut App) {
+    let sample = match transaction::find_hash(&res.stderr).or_else(|| transaction::find_hash(&res.result)) {
+        Some(hash) => CostSample::submitted(app.network, &hash),
+        None => simulation::simulate_invoke(args).and_then(|(envelope, simulated)| {
+            CostSample::simulated(app.network, &envelope, &simulated).ok_or_else(|| anyhow!("not a contract call"))
+        }),
+    };
+    match sample {
+        Ok(sample) => app.cost_samples.push(sample),
+        Err(error) => app.print_lines([Line::from(format!("Costs not recorded: {error:#}").dim())]),
+    }
+}
+
+/// Form restoring an archived persistent entry, optionally extending its TTL afterwards.
+fn restore_form(args: &ContractDataArgs) -> Form {
+    Form::new(
+        FormKind::RestoreEntry,
+        vec![
+            FormField::text("Contract ID", &args.contract_id),
+            FormField::text("Key (symbol)", &args.key),
+            FormField::text("Key XDR", &args.key_xdr),
+            FormField::text("Extend after restore (ledgers)", "0"),
+            FormField::text("Source account", &args.source_account),
+        ],
+    )
+}
+
+fn instance_form(contract_id: &str, path: &str) -> Form {
+    Form::new(
+        FormKind::BrowseInstanceStorage,
+        vec![FormField::text("Contract ID", contract_id), FormField::text("Path", path)],
+    )
+}
+
+/// Entry the read, extend and restore scripts start from: the last generated data key,
+/// or the current contract's instance.
+fn target_args(app: &App, instance: bool) -> ContractDataArgs {
+    let args = ContractDataArgs {
+        network: app.network,
+        contract_id: app.contract_id.clone(),
+        durability: "persistent".to_string(),
+        source_account: app.source_account.clone(),
+        ..Default::default()
+    };
+    match &app.data_key {
+        Some(data_key) if !instance => ContractDataArgs {
+            source_account: app.source_account.clone(),
+            ..data_key.clone()
+        },
+        _ => args,
+    }
+}
+
+/// Collects the contract data entry addressed by a form.
+fn contract_data_args(form: &Form, app: &App) -> ContractDataArgs {
+    let durability = form.value("Durability");
+    ContractDataArgs {
+        network: app.network,
+        contract_id: form.value("Contract ID").to_string(),
+        durability: if durability.is_empty() { "persistent" } else { durability }.to_string(),
+        key: form.value("Key (symbol)").to_string(),
+        key_xdr: form.value("Key XDR").to_string(),
+        source_account: form.value("Source account").to_string(),
+        build_only: app.build_only,
+    }
+}
+
+/// Reads a contract data entry and flags it if it has been archived.
+fn read_contract_data(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let args = contract_data_args(form, app);
+    app.contract_id = args.contract_id.clone();
+    // `r` restores the entry of the latest read only.
+    app.pending_restore = None;
+
+    let res = execute(ContractRead(args.clone()));
+    app.print_cmd_response(&res);
+
+    let key = ledger_key::contract_data_key(&args)?;
+    remember_storage_keys(std::iter::once(&key), app);
+    let durability = match &key {
+        LedgerKey::ContractData(data_key) => data_key.durability,
+        _ => ContractDataDurability::Persistent,
+    };
+    let status = archival::check(app.network, &key)?;
+    app.print_lines([status.to_line(durability)]);
+
+    // The ledger decides, the CLI's error text can mention restoring for unrelated failures.
+    if status.needs_restore(durability) {
+        app.print_lines([Line::from("Press `r` to restore the entry and optionally extend its TTL".yellow().bold())]);
+        app.pending_restore = Some(ContractDataArgs {
+            durability: "persistent".to_string(),
+            source_account: app.source_account.clone(),
+            ..args
+        });
+    }
+    Ok(())
+}
+
+fn extend_ttl(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let args = contract_data_args(form, app);
+    let ledgers = form.parse("Extend by (ledgers)")?;
+    app.contract_id = args.contract_id.clone();
+    app.source_account = args.source_account.clone();
+
+    let res = execute(ContractExtend(args, ledgers));
+    if app.build_only {
+        return print_unsigned(&res, app);
+    }
+    app.print_cmd_response(&res);
+    Ok(())
+}
+
+/// Restores a persistent entry, then extends it if a number of ledgers was given.
+fn restore_entry(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let args = contract_data_args(form, app);
+    let extend_ledgers: u32 = form.parse("Extend after restore (ledgers)")?;
+    app.contract_id = args.contract_id.clone();
+    app.source_account = args.source_account.clone();
+
+    let res = execute(ContractRestore(args.clone()));
+    if args.build_only {
+        print_unsigned(&res, app)?;
+        if extend_ledgers > 0 {
+            app.print_lines([Line::from("Extend the entry once the restore is submitted, it needs the next sequence number".dim())]);
+        }
+        return Ok(());
+    }
+    app.print_cmd_response(&res);
+    if res.success && extend_ledgers > 0 {
+        let res = execute(ContractExtend(args, extend_ledgers));
+        app.print_cmd_response(&res);
+    }
+    Ok(())
+}
+
+fn calculate_rent(form: &Form, app: &App) -> anyhow::Result<Vec<Line<'static>>> {
+    let entry_size = form.parse("Entry size (bytes)")?;
+    let durability = ledger_key::parse_durability(form.value("Durability"))?;
+    let ledgers = form.parse("Extend by (ledgers)")?;
+    rent::compare_extensions(app.network, durability, entry_size, ledgers)
+}
+
+/// Builds a `LedgerKey::ContractData` and keeps its key for the other scripts.
+fn generate_data_key(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let key = ledger_key::parse_scval(form.value("Key type"), form.value("Key value"))?;
+    let args = ContractDataArgs {
+        key_xdr: key.to_xdr_base64(Limits::none())?,
+        ..contract_data_args(form, app)
+    };
+
+    let lines = ledger_key::render_data_key(&args)?;
+    app.print_lines(lines);
+    app.contract_id = args.contract_id.clone();
+    app.data_key = Some(args);
+    Ok(())
+}
+
+/// Looks up an arbitrary ledger entry, from a pasted key or one built from the form.
+fn explore_ledger_entry(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let key = match form.value("Key kind") {
+        "account" => ledger_key::account_key(form.value("Account ID"))?,
+        "trustline" => ledger_key::trustline_key(form.value("Account ID"), form.value("Asset (CODE:ISSUER)"))?,
+        "contract code" => ledger_key::contract_code_key(form.value("Wasm hash"))?,
+        "contract data" => {
+            let key = ledger_key::parse_scval(form.value("Key type"), form.value("Key value"))?;
+            ledger_key::contract_data_key(&ContractDataArgs {
+                key_xdr: key.to_xdr_base64(Limits::none())?,
+                ..contract_data_args(form, app)
+            })?
+        }
+        _ => ledger_key::ledger_key_from_xdr(form.value("LedgerKey XDR"))?,
+    };
+
+    let lines = ledger_entry::render(app.network, &key)?;
+    app.print_lines(lines);
+    Ok(())
+}
+
+/// Lists the target contract's events, then keeps appending new ones in follow mode.
+fn show_contract_events(form: &Form, app: &mut App, event_handler: &EventHandler) -> anyhow::Result<()> {
+    let optional_ledger = |label| -> anyhow::Result<Option<u32>> {
+        if form.value(label).is_empty() { Ok(None) } else { form.parse(label).map(Some) }
+    };
+    let mut query = EventsQuery {
+        network: app.network,
+        contract_id: form.value("Contract ID").to_string(),
+        start_ledger: optional_ledger("Start ledger")?,
+        end_ledger: optional_ledger("End ledger")?,
+        event_type: form.value("Event type").to_string(),
+        topics: events::parse_topic_filter(form.value("Topic filter"))?,
+        limit: form.parse("Limit")?,
+        cursor: None,
+    };
+    app.contract_id = query.contract_id.clone();
+    app.stop_following_events();
+
+    let page = events::fetch(&query)?;
+    query.advance(&page);
+    let found = page.lines.len();
+    app.print_lines([Line::from(format!("Events of {} on {}", query.contract_id, app.network).bold())]);
+    app.print_lines(page.lines);
+    app.print_lines([Line::from(format!("{found} events, latest ledger {}", page.latest_ledger).dim())]);
+
+    if form.value("Follow new events") == "yes" {
+        query.end_ledger = None;
+        let stop = Arc::new(AtomicBool::new(false));
+        events::follow(query, event_handler.sender(), stop.clone());
+        app.events_follow = Some(stop);
+        app.print_lines([Line::from("Following new events, press `f` to stop".yellow())]);
+    }
+    Ok(())
+}
+
+/// Invokes a contract function, explaining the failure from its diagnostic events if it fails.
+fn invoke_contract(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let args = InvokeArgs {
+        network: app.network,
+        contract_id: form.value("Contract ID").to_string(),
+        source_account: form.value("Source account").to_string(),
+        function: form.value("Function").to_string(),
+        args: form.value("Arguments").to_string(),
+        build_only: app.build_only,
+    };
+    if args.function.is_empty() {
+        bail!("enter the function to invoke");
+    }
+    app.contract_id = args.contract_id.clone();
+    app.source_account = args.source_account.clone();
+
+    let res = execute(ContractInvoke(args.clone()));
+    if args.build_only {
+        return print_unsigned(&res, app);
+    }
+    app.print_cmd_response(&res);
+    if res.success {
+        record_invocation_cost(&args, &res, app);
+    } else {
+        let explanation = diagnostics::explain(&args, &res.stderr);
+        app.print_result(explanation);
+    }
+    Ok(())
+}
+
+/// Simulates an invocation and lists the ledger entries it would read and write.
+fn preview_footprint(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let args = InvokeArgs {
+        network: app.network,
+        contract_id: form.value("Contract ID").to_string(),
+        source_account: form.value("Source account").to_string(),
+        function: form.value("Function").to_string(),
+        args: form.value("Arguments").to_string(),
+        build_only: true,
+    };
+    if args.function.is_empty() {
+        bail!("enter the function to invoke");
+    }
+    app.contract_id = args.contract_id.clone();
+    app.source_account = args.source_account.clone();
+
+    let (envelope, simulated) = simulation::simulate_invoke(&args)?;
+    record_simulated_cost(&envelope, &simulated, app);
+    app.print_lines([Line::from(format!("{} of {} on {}", args.function, args.contract_id, app.network).bold())]);
+    print_footprint(&envelope, app);
+    Ok(())
+}
+
+/// Simulates an invocation and shows the authorizations it needs, then offers to sign them
+/// with local identities.
+fn review_authorizations(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let args = InvokeArgs {
+        network: app.network,
+        contract_id: form.value("Contract ID").to_string(),
+        source_account: form.value("Source account").to_string(),
+        function: form.value("Function").to_string(),
+        args: form.value("Arguments").to_string(),
+        build_only: true,
+    };
+    if args.function.is_empty() {
+        bail!("enter the function to invoke");
+    }
+    app.contract_id = args.contract_id.clone();
+    app.source_account = args.source_account.clone();
+
+    let (envelope, simulated) = simulation::simulate_invoke(&args)?;
+    record_simulated_cost(&envelope, &simulated, app);
+    let entries = simulation::invoke_op(&envelope)?.auth.to_vec();
+    app.print_lines([Line::from(format!("{} of {} on {}", args.function, args.contract_id, app.network).bold())]);
+    app.print_lines(auth::render(&entries));
+    print_footprint(&envelope, app);
+
+    app.unsigned_tx = Some(envelope.to_xdr_base64(Limits::none())?);
+    app.form = Some(Form::new(
+        FormKind::SignAuthorizations,
+        vec![
+            FormField::text("Sign with identities (comma separated)", ""),
+            FormField::text("Source account", &args.source_account),
+            FormField::picker("Submit", &["no", "yes"]),
+        ],
+    ));
+    Ok(())
+}
+
+/// Signs the reviewed invocation's authorizations with local identities, then submits it
+/// or prints the signed transaction.
+fn sign_authorizations(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let Some(unsigned_tx) = app.unsigned_tx.take() else {
+        bail!("review an invocation's authorizations first");
+    };
+    let mut envelope = TransactionEnvelope::from_xdr_base64(&unsigned_tx, Limits::none())?;
+    let mut entries = simulation::invoke_op(&envelope)?.auth.to_vec();
+    let expiration_ledger = events::latest_ledger(app.network)? + auth::SIGNATURE_VALIDITY_LEDGERS;
+
+    let identities = form
+        .value("Sign with identities (comma separated)")
+        .split(',')
+        .map(str::trim)
+        .filter(|identity| !identity.is_empty());
+    for identity in identities {
+        let key = auth::signing_key(identity)?;
+        match auth::sign_entries(&mut entries, &key, app.network, expiration_ledger)? {
+            0 => app.print_lines([Line::from(format!("{identity} authorizes none of the entries").yellow())]),
+            signed => app.print_lines([Line::from(format!("Signed {signed} entries as {identity}").green())]),
+        }
+    }
+    simulation::set_auth(&mut envelope, entries.clone())?;
+
+    // Verifying the signatures costs resources the first simulation didn't account for.
+    let simulated = simulation::simulate(app.network, &envelope)?;
+    simulation::assemble(&mut envelope, &simulated)?;
+    let signed_tx = envelope.to_xdr_base64(Limits::none())?;
+    app.print_lines(auth::render(&entries));
+
+    let unsigned = auth::unsigned_addresses(&entries);
+    if !unsigned.is_empty() {
+        app.print_lines([Line::from(format!("Still waiting for signatures of {}", unsigned.join(", ")).yellow())]);
+    }
+    if form.value("Submit") != "yes" || app.build_only {
+        app.print_lines([Line::from("Signed transaction, not submitted:".bold()), Line::raw(signed_tx.clone())]);
+        app.unsigned_tx = Some(signed_tx);
+        return Ok(());
+    }
+
+    let source_account = form.value("Source account").to_string();
+    if source_account.is_empty() {
+        bail!("enter the source account signing the transaction");
+    }
+    let res = execute(TxSign(app.network, source_account, signed_tx));
+    if !res.success {
+        app.print_cmd_response(&res);
+        return Ok(());
+    }
+    let res = execute(TxSend(app.network, res.result.trim().to_string()));
+    app.print_cmd_response(&res);
+    Ok(())
+}
+
+/// First step of the deploy flow: uploads the wasm, then opens the deploy step with its hash
+/// and a constructor argument template from the wasm's spec.
+fn upload_wasm(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let args = DeployArgs {
+        network: app.network,
+        source_account: form.value("Source account").to_string(),
+        wasm: form.value("Wasm file").to_string(),
+        build_only: app.build_only,
+        ..Default::default()
+    };
+    if args.wasm.is_empty() {
+        bail!("enter the path of the wasm file to deploy");
+    }
+    app.wasm_path = args.wasm.clone();
+    app.source_account = args.source_account.clone();
+
+    let res = execute(ContractUpload(args.clone()));
+    if !res.success {
+        app.print_cmd_response(&res);
+        return Ok(());
+    }
+    let wasm_hash = if args.build_only {
+        print_unsigned(&res, app)?;
+        // The deploy is simulated against the ledger, which only has the wasm once the upload is
+        // submitted.
+        let wasm_hash = wasm::local_hash(&args.wasm)?;
+        app.print_lines([Line::from(format!(
+            "Wasm {wasm_hash} is uploaded once this transaction is signed and submitted, submit it before building the deploy"
+        ).yellow())]);
+        wasm_hash
+    } else {
+        app.print_cmd_response(&res);
+        let wasm_hash = res.result.lines().last().unwrap_or_default().trim().to_string();
+        app.print_lines([Line::from(format!("Uploaded wasm {wasm_hash}, continue with the deploy step").green())]);
+        wasm_hash
+    };
+
+    let constructor_args = match spec::read_wasm(&args.wasm) {
+        Ok(spec) => spec::function(&spec, "__constructor").map(spec::args_template).unwrap_or_default(),
+        Err(error) => {
+            app.print_lines([Line::from(format!("No constructor template: {error:#}").dim())]);
+            String::new()
+        }
+    };
+    app.form = Some(Form::new(
+        FormKind::DeployContract,
+        vec![
+            FormField::text("Wasm hash", &wasm_hash),
+            FormField::text("Salt", ""),
+            FormField::text("Constructor arguments", &constructor_args),
+            FormField::text("Alias", ""),
+            FormField::text("Source account", &args.source_account),
+        ],
+    ));
+    Ok(())
+}
+
+/// Deploys an uploaded wasm and makes the new contract the current target.
+fn deploy_contract(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let args = DeployArgs {
+        network: app.network,
+        source_account: form.value("Source account").to_string(),
+        wasm_hash: form.value("Wasm hash").to_string(),
+        salt: form.value("Salt").to_string(),
+        alias: form.value("Alias").to_string(),
+        constructor_args: form.value("Constructor arguments").to_string(),
+        build_only: app.build_only,
+        ..Default::default()
+    };
+    if args.constructor_args.contains('<') {
+        bail!("fill in the constructor arguments: {}", args.constructor_args);
+    }
+    app.source_account = args.source_account.clone();
+    if args.build_only && !wasm::is_uploaded(app.network, &args.wasm_hash)? {
+        bail!("wasm {} isn't on {} yet, submit its upload before building the deploy", args.wasm_hash, app.network);
+    }
+
+    let res = execute(ContractDeploy(args.clone()));
+    if args.build_only {
+        return print_unsigned(&res, app);
+    }
+    app.print_cmd_response(&res);
+    if let Some(contract_id) = res.result.lines().last().map(str::trim).filter(|_| res.success) {
+        app.contract_id = contract_id.to_string();
+        app.print_lines([Line::from(format!("Deployed {contract_id}, now the current contract").green().bold())]);
+    }
+    Ok(())
+}
+
+/// First step of the upgrade flow: compares the deployed wasm and interface with a local build,
+/// then asks to confirm the upgrade.
+fn compare_upgrade(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let contract_id = form.value("Contract ID").to_string();
+    let wasm_path = form.value("Wasm file").to_string();
+    app.contract_id = contract_id.clone();
+    app.wasm_path = wasm_path.clone();
+
+    let deployed_hash = wasm::deployed_hash(app.network, &contract_id)?;
+    let local_hash = wasm::local_hash(&wasm_path)?;
+    app.print_lines([
+        Line::from(format!("Upgrade {contract_id} on {}", app.network).bold()),
+        Line::raw(format!("Deployed wasm: {deployed_hash}")),
+        Line::raw(format!("Local wasm:    {local_hash}")),
+    ]);
+    if deployed_hash == local_hash {
+        app.print_lines([Line::from("The contract already runs this wasm, nothing to upgrade".green())]);
+        return Ok(());
+    }
+
+    let deployed_spec = spec::fetch(app.network, &contract_id).unwrap_or_else(|error| {
+        app.print_lines([Line::from(format!("Deployed interface unavailable: {error:#}").dim())]);
+        Vec::new()
+    });
+    let changes = spec::diff(&deployed_spec, &spec::read_wasm(&wasm_path)?);
+    app.print_lines([Line::from("Interface changes".bold())]);
+    if changes.is_empty() {
+        app.print_lines([Line::raw("No interface changes")]);
+    }
+    app.print_lines(changes);
+
+    // The upgrade function belongs to the deployed code, its wasm hash argument gets the new hash.
+    let upgrade_args = match spec::function(&deployed_spec, "upgrade") {
+        Some(function) => function
+            .inputs
+            .iter()
+            .map(|input| match &input.type_ {
+                ScSpecTypeDef::BytesN(bytes) if bytes.n == 32 => {
+                    format!("--{} {local_hash}", input.name.to_utf8_string_lossy())
+                }
+                type_def => format!("--{} <{}>", input.name.to_utf8_string_lossy(), spec::type_name(type_def)),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => format!("--new_wasm_hash {local_hash}"),
+    };
+    app.form = Some(Form::new(
+        FormKind::ConfirmUpgrade,
+        vec![
+            FormField::text("Contract ID", &contract_id),
+            FormField::text("Wasm file", &wasm_path),
+            FormField::text("Upgrade function", "upgrade"),
+            FormField::text("Arguments", &upgrade_args),
+            FormField::text("Source account", &app.source_account),
+            FormField::picker("Confirm upgrade", &["no", "yes"]),
+        ],
+    ));
+    Ok(())
+}
+
+/// Uploads the new wasm and invokes the contract's upgrade function with its hash.
+fn confirm_upgrade(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    if form.value("Confirm upgrade") != "yes" {
+        app.print_lines([Line::from("Upgrade cancelled".dim())]);
+        return Ok(());
+    }
+    let upgrade_args = form.value("Arguments").to_string();
+    // Checked before the upload, a paid transaction, rather than when the invoke fails.
+    if upgrade_args.contains('<') {
+        bail!("fill in the upgrade arguments: {upgrade_args}");
+    }
+    let source_account = form.value("Source account").to_string();
+    app.source_account = source_account.clone();
+
+    let upload = DeployArgs {
+        network: app.network,
+        source_account: source_account.clone(),
+        wasm: form.value("Wasm file").to_string(),
+        build_only: app.build_only,
+        ..Default::default()
+    };
+    let args = InvokeArgs {
+        network: app.network,
+        contract_id: form.value("Contract ID").to_string(),
+        source_account,
+        function: form.value("Upgrade function").to_string(),
+        args: upgrade_args,
+        build_only: app.build_only,
+    };
+
+    // The upgrade invocation is simulated against the ledger, so it can only be built once the
+    // upload of the local wasm is submitted.
+    if app.build_only {
+        let wasm_hash = wasm::local_hash(&upload.wasm)?;
+        if wasm::is_uploaded(app.network, &wasm_hash)? {
+            let res = execute(ContractInvoke(args));
+            return print_unsigned(&res, app);
+        }
+        let res = execute(ContractUpload(upload));
+        print_unsigned(&res, app)?;
+        if res.success {
+            app.print_lines([Line::from(format!(
+                "Wasm {wasm_hash} is uploaded once this transaction is signed and submitted, then confirm the upgrade again to build it"
+            ).yellow())]);
+        }
+        return Ok(());
+    }
+
+    let res = execute(ContractUpload(upload));
+    app.print_cmd_response(&res);
+    if !res.success {
+        return Ok(());
+    }
+
+    let res = execute(ContractInvoke(args.clone()));
+    app.print_cmd_response(&res);
+    if !res.success {
+        let explanation = diagnostics::explain(&args, &res.stderr);
+        app.print_result(explanation);
+        return Ok(());
+    }
+    record_invocation_cost(&args, &res, app);
+    let deployed_hash = wasm::deployed_hash(app.network, &args.contract_id)?;
+    app.print_lines([Line::from(format!("Upgraded, the contract now runs wasm {deployed_hash}").green().bold())]);
+    Ok(())
+}
+
+/// Decodes a wasm file, fetching the code of a deployed contract into it first.
+fn inspect_wasm(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let mut path = form.value("Wasm file").to_string();
+    if form.value("Source") == "contract" {
+        let contract_id = form.value("Contract ID").to_string();
+        if path.is_empty() {
+            path = format!("{contract_id}.wasm");
+        }
+        app.contract_id = contract_id.clone();
+
+        let res = execute(ContractFetch(app.network, contract_id, path.clone()));
+        app.print_cmd_response(&res);
+        if !res.success {
+            return Ok(());
+        }
+        app.print_lines([Line::from(format!("Saved the contract wasm to {path}").green())]);
+    }
+
+    let lines = wasm::inspect(&path)?;
+    app.print_lines(lines);
+    Ok(())
+}
+
+/// Starts a build of the workspace, its output is streamed to the command output.
+fn build_contract(form: &Form, app: &mut App, event_handler: &EventHandler) -> anyhow::Result<()> {
+    let workspace = form.value("Workspace");
+    app.workspace = if workspace.is_empty() { "." } else { workspace }.to_string();
+    app.print_lines([Line::from(format!("$ stellar contract build in {}", app.workspace).dim())]);
+    build::run(app.workspace.clone(), form.value("Package").to_string(), event_handler.sender());
+    Ok(())
+}
+
+/// Continues with the deploy or upgrade flow for a build artifact.
+fn deploy_artifact(form: &Form, app: &mut App, event_handler: &EventHandler) -> anyhow::Result<()> {
+    app.wasm_path = form.value("Artifact").to_string();
+    let next_item = match form.value("Action") {
+        "upgrade" => MenuItem::UpgradeContract,
+        _ => MenuItem::DeployContract,
+    };
+    run_menu_item(next_item, app, event_handler).map_err(|error| anyhow!("{error}"))
+}
+
+/// Generates client bindings for a contract, then shows the generated files and interface.
+fn generate_bindings(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let mut args = BindingsArgs {
+        network: app.network,
+        language: form.value("Language").to_string(),
+        contract_id: form.value("Contract ID").to_string(),
+        output_dir: form.value("Output directory").to_string(),
+        ..Default::default()
+    };
+    if args.output_dir.is_empty() {
+        bail!("enter the directory to generate the bindings into");
+    }
+    app.contract_id = args.contract_id.clone();
+    std::fs::create_dir_all(&args.output_dir)?;
+
+    // The Rust and JSON generators read a wasm file and print the bindings, it's fetched outside
+    // the output directory so the generated package doesn't ship it.
+    if args.language != "typescript" {
+        let wasm = std::env::temp_dir().join(format!("{}-{}.wasm", args.contract_id, std::process::id()));
+        args.wasm = wasm.to_string_lossy().to_string();
+        let res = execute(ContractFetch(app.network, args.contract_id.clone(), args.wasm.clone()));
+        app.print_cmd_response(&res);
+        if !res.success {
+            let _ = std::fs::remove_file(&wasm);
+            return Ok(());
+        }
+    }
+
+    let res = execute(ContractBindings(args.clone()));
+    if !args.wasm.is_empty() {
+        let _ = std::fs::remove_file(&args.wasm);
+    }
+    if args.language == "typescript" || !res.success {
+        app.print_cmd_response(&res);
+    } else {
+        app.print_lines([Line::from(format!("$ {}", res.raw_cmd).dim())]);
+    }
+    if !res.success {
+        return Ok(());
+    }
+    if args.language != "typescript" {
+        std::fs::write(bindings::output_file(&args.language, &args.output_dir), &res.result)?;
+    }
+
+    let tree = bindings::file_tree(&args.output_dir)?;
+    app.print_lines(tree);
+    app.print_lines([Line::raw("")]);
+    let preview = bindings::preview(&bindings::interface_file(&args.language, &args.output_dir))?;
+    app.print_lines(preview);
+    Ok(())
+}
+
+/// Resolves the asset's contract, deploying it on request, and shows its metadata and balances.
+fn show_asset_contract(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let asset = form.value("Asset (CODE:ISSUER or native)").to_string();
+    let source_account = form.value("Source account").to_string();
+    app.source_account = source_account.clone();
+
+    let contract_id = sac::contract_id(app.network, &asset)?;
+    app.print_lines([
+        Line::from(format!("Stellar Asset Contract of {asset} on {}", app.network).bold()),
+        Line::raw(format!("Contract ID: {contract_id}")),
+    ]);
+
+    if !sac::is_deployed(app.network, &contract_id)? {
+        if form.value("Deploy if missing") != "yes" {
+            app.print_lines([Line::from("Not deployed yet, pick `Deploy if missing` to deploy it".yellow())]);
+            return Ok(());
+        }
+        let res = execute(AssetDeploy(app.network, asset, source_account.clone(), app.build_only));
+        // The metadata and balances are read from the deployed contract, which doesn't exist
+        // until the unsigned deploy is submitted.
+        if app.build_only {
+            return print_unsigned(&res, app);
+        }
+        app.print_cmd_response(&res);
+        if !res.success {
+            return Ok(());
+        }
+    }
+    app.contract_id = contract_id.clone();
+
+    let addresses: Vec<&str> = form
+        .value("Balances of (comma separated)")
+        .split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .collect();
+    let lines = sac::render_info(app.network, &contract_id, &source_account, &addresses);
+    app.print_lines(lines);
+    Ok(())
+}
+
+/// Runs a token action against the current contract and shows the events it emitted.
+fn run_token_action(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let action = form.value("Action");
+    let token_spec = cached_token_spec(app)?;
+    let args = InvokeArgs {
+        network: app.network,
+        contract_id: app.contract_id.clone(),
+        source_account: form.value("Source account").to_string(),
+        function: action.to_string(),
+        args: token::action_args(&token_spec.spec, action, |label| form.value(label).to_string())?,
+        build_only: app.build_only && !token::READ_ACTIONS.contains(&action),
+    };
+    app.source_account = args.source_account.clone();
+
+    let res = execute(ContractInvoke(args.clone()));
+    if args.build_only {
+        return print_unsigned(&res, app);
+    }
+    app.print_cmd_response(&res);
+    if !res.success {
+        let explanation = diagnostics::explain(&args, &res.stderr);
+        app.print_result(explanation);
+        return Ok(());
+    }
+    record_invocation_cost(&args, &res, app);
+    if !token::READ_ACTIONS.contains(&action) {
+        if let Some(hash) = transaction::find_hash(&res.stderr) {
+            let events = transaction::render_events(app.network, &hash);
+            app.print_result(events);
+        }
+    }
+    Ok(())
+}
+
+/// Spec of the current contract for the token dashboard, fetched when the contract changed.
+fn cached_token_spec(app: &mut App) -> anyhow::Result<TokenSpec> {
+    match &app.token_spec {
+        Some(token_spec) if token_spec.is_for(app.network, &app.contract_id) => Ok(token_spec.clone()),
+        _ => {
+            let token_spec = TokenSpec::fetch(app.network, &app.contract_id)?;
+            app.token_spec = Some(token_spec.clone());
+            Ok(token_spec)
+        }
+    }
+}
+
+fn save_transaction(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let Some(xdr) = &app.unsigned_tx else {
+        bail!("there is no transaction to save");
+    };
+    let path = form.value("File");
+    envelope::save(path, xdr)?;
+    app.print_lines([Line::from(format!("Saved the transaction to {path}").green())]);
+    Ok(())
+}
+
+/// Decodes a transaction signed elsewhere, pasted or read from the file it was saved to, then
+/// sends it and follows it until it's applied.
+fn submit_signed_transaction(form: &Form, app: &mut App, event_handler: &EventHandler) -> anyhow::Result<()> {
+    let envelope = envelope::read(form.value("Signed envelope (XDR or file)"))?;
+    app.print_lines([Line::from(format!("Signed transaction for {}", app.network).bold())]);
+    app.print_lines(envelope::describe(&envelope));
+    print_footprint(&envelope, app);
+    if form.value("Send") != "yes" {
+        app.print_lines([Line::from("Not sent, pick `Send` to submit it".dim())]);
+        return Ok(());
+    }
+
+    let hash = transaction::send(app.network, &envelope)?;
+    app.print_lines([Line::from(format!("Sent transaction {hash}").green())]);
+    app.tx_hash = hash.clone();
+    transaction::track(app.network, hash, event_handler.sender());
+    Ok(())
+}
+
+/// Lists a contract's instance storage, or the nested value at the form's path.
+fn browse_instance_storage(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    app.contract_id = form.value("Contract ID").to_string();
+    let path = form.value("Path").trim_matches('/').to_string();
+    let lines = instance::render(app.network, &app.contract_id, &path)?;
+    app.print_lines(lines);
+    app.instance_path = Some(path);
+    Ok(())
+}
+
+/// Saves the known storage entries of a contract to a file, for diffing later.
+fn snapshot_storage(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    app.contract_id = form.value("Contract ID").to_string();
+    let path = form.value("File");
+    let contract = ledger_key::parse_address(&app.contract_id)?;
+    let keys = storage::known_keys(&contract, &app.storage_keys);
+
+    let snapshot = storage::snapshot(app.network, &keys)?;
+    snapshot.save(path)?;
+    app.print_lines([
+        Line::from(format!("Saved {} storage entries of {} to {path}", snapshot.ledger_entries.len(), app.contract_id).bold()),
+        Line::raw(format!("{} keys known at ledger {}", keys.len(), snapshot.sequence_number)),
+        Line::from("Keys are learnt from footprints and reads, preview invocations to find more".dim()),
+    ]);
+    Ok(())
+}
+
+/// Diffs a storage snapshot against another one or against the live ledger.
+fn diff_storage(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let before = Snapshot::load(form.value("Before"))?;
+    let after_path = form.value("After (file or live)");
+    let live = matches!(after_path, "" | "live");
+    let after = if live {
+        app.contract_id = form.value("Contract ID").to_string();
+        let contract = ledger_key::parse_address(&app.contract_id)?;
+        let mut keys = storage::known_keys(&contract, &app.storage_keys);
+        for entry in &before.ledger_entries {
+            let key = entry.entry.to_key();
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        storage::snapshot(app.network, &keys)?
+    } else {
+        Snapshot::load(after_path)?
+    };
+    let lines = storage::diff(&before, &after, live);
+    app.print_lines(lines);
+    Ok(())
+}
+
+/// Saves the ledger entries an invocation touches into a snapshot for the sandbox.
+fn capture_snapshot(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let args = InvokeArgs {
+        network: app.network,
+        contract_id: form.value("Contract ID").to_string(),
+        source_account: form.value("Source account").to_string(),
+        function: form.value("Function").to_string(),
+        args: form.value("Arguments").to_string(),
+        build_only: true,
+    };
+    if args.function.is_empty() {
+        bail!("enter the function to invoke");
+    }
+    app.contract_id = args.contract_id.clone();
+    app.source_account = args.source_account.clone();
+    app.snapshot_path = form.value("Snapshot file").to_string();
+
+    let lines = sandbox::capture(&args, &app.snapshot_path)?;
+    app.print_lines(lines);
+    Ok(())
+}
+
+/// Runs a contract function offline against a ledger snapshot, optionally keeping its
+/// storage changes in the snapshot file.
+fn run_in_sandbox(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let call = SandboxCall {
+        contract_id: form.value("Contract ID").to_string(),
+        function: form.value("Function").to_string(),
+        args: form.value("Arguments").to_string(),
+        source_account: form.value("Source account").to_string(),
+    };
+    if call.function.is_empty() {
+        bail!("enter the function to run");
+    }
+    app.snapshot_path = form.value("Snapshot file").to_string();
+    app.contract_id = call.contract_id.clone();
+
+    let mut snapshot = Snapshot::load(&app.snapshot_path)?;
+    let keep = form.value("Keep changes") == "yes";
+    let lines = sandbox::run(&mut snapshot, &call, keep)?;
+    app.print_lines(lines);
+    if keep {
+        snapshot.save(&app.snapshot_path)?;
+        app.print_lines([Line::from(format!("Changes kept in {}", app.snapshot_path).dim())]);
+    }
+    Ok(())
+}
+
+/// Shows an account decoded and keeps it for refreshing with `a`.
+fn inspect_account(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let account_id = account::resolve(form.value("Address or identity"))?;
+    let lines = account::render(app.network, &account_id)?;
+    app.print_lines(lines);
+    app.print_lines([Line::from("Press `a` to refresh the account".dim())]);
+    app.inspected_account = Some(account_id);
+    Ok(())
+}
+
+/// Funds an account with friendbot, refusing to run against mainnet.
+fn fund_account(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let Some(default_url) = app.network.friendbot_url() else {
+        bail!("refusing to use a friendbot on {}", app.network);
+    };
+    let friendbot_url = form.value("Friendbot URL").to_string();
+    if friendbot_url == default_url {
+        app.friendbot_urls.remove(&app.network);
+    } else {
+        app.friendbot_urls.insert(app.network, friendbot_url.clone());
+    }
+
+    let account_id = account::resolve(form.value("Address or identity"))?;
+    let hash = account::fund(&friendbot_url, &account_id)?;
+    app.print_lines([
+        Line::from(format!("Funded {account_id} on {}", app.network).green().bold()),
+        Line::raw(format!("Funding transaction: {hash}")),
+    ]);
+    app.tx_hash = hash;
+    Ok(())
+}
+
+/// Shows a transaction's status, return value, events and ledger entry changes.
+fn look_up_transaction(form: &Form, app: &mut App) -> anyhow::Result<()> {
+    let hash = form.value("Transaction hash").to_lowercase();
+    let lines = transaction::render(app.network, &hash)?;
+    app.print_lines(lines);
+    app.tx_hash = hash;
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use anyhow::bail;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use stellar_xdr::curr::{
+    ContractDataDurability, ContractExecutable, LedgerEntryData, LedgerKey, LedgerKeyContractData, ScVal,
+};
+
+use crate::archival::EntryStatus;
+use crate::network::StellarNetwork;
+use crate::{arguments, ledger_key, rpc, scval};
+
+/// Browses the instance storage of a contract, which lives inside its instance entry and shares
+/// its TTL.
+///
+/// `path` selects a nested value by `/` separated keys or indexes, e.g. `Admin` or `Config/2`,
+/// the whole storage map is listed when it is empty. Keys containing `/` are quoted,
+/// `"fees/2024"/0`.
+pub fn render(network: StellarNetwork, contract_id: &str, path: &str) -> anyhow::Result<Vec<Line<'static>>> {
+    let key = LedgerKey::ContractData(LedgerKeyContractData {
+        contract: ledger_key::parse_address(contract_id)?,
+        key: ScVal::LedgerKeyContractInstance,
+        durability: ContractDataDurability::Persistent,
+    });
+    let ledger_entries = rpc::get_ledger_entries(network, std::slice::from_ref(&key))?;
+    let Some(entry) = ledger_entries.entries.first() else {
+        bail!("{contract_id} has no instance on {network}, it was never deployed or has been archived");
+    };
+    let LedgerEntryData::ContractData(data) = &entry.data else {
+        bail!("the instance of {contract_id} is not a contract data entry");
+    };
+    let ScVal::ContractInstance(instance) = &data.val else {
+        bail!("the instance entry of {contract_id} holds no contract instance");
+    };
+
+    let executable = match &instance.executable {
+        ContractExecutable::Wasm(hash) => format!("wasm {hash}"),
+        ContractExecutable::StellarAsset => "stellar asset".to_string(),
+    };
+    let storage = ScVal::Map(instance.storage.clone());
+    let mut lines = vec![
+        Line::from(format!("Instance storage of {contract_id} ({} keys)", children(&storage)).bold()),
+        Line::raw(format!("Executable: {executable}")),
+    ];
+    let mut ttl = EntryStatus::of(entry, ledger_entries.latest_ledger).to_line(ContractDataDurability::Persistent);
+    ttl.spans.insert(0, "Shared TTL: ".into());
+    lines.push(ttl);
+
+    let mut value = &storage;
+    let mut segments = Vec::new();
+    for segment in arguments::split_quoted(path, '/')?.into_iter().map(str::trim).filter(|segment| !segment.is_empty()) {
+        let Some(child) = child(value, segment) else {
+            bail!("`{}` has no key or index `{segment}`", display_path(&segments));
+        };
+        value = child;
+        segments.push(segment);
+    }
+    lines.push(Line::from(format!("{} ({})", display_path(&segments), value.name()).cyan()));
+
+    match value {
+        ScVal::Map(Some(map)) => {
+            if map.is_empty() {
+                lines.push(Line::raw("  empty"));
+            }
+            for (index, entry) in map.iter().enumerate() {
+                lines.push(Line::raw(format!("  [{index}] {} = {}", scval::format(&entry.key), summary(&entry.val))));
+            }
+        }
+        ScVal::Vec(Some(vec)) => {
+            if vec.is_empty() {
+                lines.push(Line::raw("  empty"));
+            }
+            for (index, element) in vec.iter().enumerate() {
+                lines.push(Line::raw(format!("  [{index}] {}", summary(element))));
+            }
+        }
+        other => lines.push(Line::raw(format!("  {}", scval::format(other)))),
+    }
+    lines.push(Line::from(
+        "Press `i` to open a nested value by its key or index, e.g. `Admin`, `Config/2` or `\"fees/2024\"`".dim(),
+    ));
+    Ok(lines)
+}
+
+/// Value stored under `segment`: a map key as formatted in the listing, the text of a string or
+/// symbol key, quoted or not, or an index.
+fn child<'a>(value: &'a ScVal, segment: &str) -> Option<&'a ScVal> {
+    let quoted = segment.starts_with('"').then(|| serde_json::from_str::<String>(segment).ok()).flatten();
+    let text = quoted.as_deref().unwrap_or(segment);
+    let index = segment.parse::<usize>().ok();
+    match value {
+        ScVal::Map(Some(map)) => map
+            .iter()
+            .find(|entry| scval::format(&entry.key) == segment || key_text(&entry.key).as_deref() == Some(text))
+            .or_else(|| map.get(index?))
+            .map(|entry| &entry.val),
+        ScVal::Vec(Some(vec)) => vec.get(index?),
+        _ => None,
+    }
+}
+
+fn key_text(key: &ScVal) -> Option<String> {
+    match key {
+        ScVal::String(string) => Some(string.to_utf8_string_lossy()),
+        ScVal::Symbol(symbol) => Some(symbol.to_utf8_string_lossy()),
+        _ => None,
+    }
+}
+
+fn children(value: &ScVal) -> usize {
+    match value {
+        ScVal::Map(Some(map)) => map.len(),
+        ScVal::Vec(Some(vec)) => vec.len(),
+        _ => 0,
+    }
+}
+
+/// Containers are summarized with their size, they are opened by drilling into them.
+fn summary(value: &ScVal) -> String {
+    match value {
+        ScVal::Map(Some(_)) => format!("map of {} ▸", children(value)),
+        ScVal::Vec(Some(_)) => format!("vec of {} ▸", children(value)),
+        other => scval::format(other),
+    }
+}
+
+fn display_path(segments: &[&str]) -> String {
+    format!("/{}", segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_xdr::curr::{ScMap, ScMapEntry, ScString, ScSymbol, ScVec};
+
+    use super::*;
+
+    fn symbol(text: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(text.try_into().unwrap()))
+    }
+
+    fn string(text: &str) -> ScVal {
+        ScVal::String(ScString(text.try_into().unwrap()))
+    }
+
+    fn sc_vec(elements: Vec<ScVal>) -> ScVal {
+        ScVal::Vec(Some(ScVec(elements.try_into().unwrap())))
+    }
+
+    fn map(entries: Vec<(ScVal, ScVal)>) -> ScVal {
+        let entries: Vec<ScMapEntry> = entries.into_iter().map(|(key, val)| ScMapEntry { key, val }).collect();
+        ScVal::Map(Some(ScMap(entries.try_into().unwrap())))
+    }
+
+    fn storage() -> ScVal {
+        map(vec![
+            (symbol("Admin"), ScVal::U32(1)),
+            (symbol("Config"), sc_vec(vec![ScVal::U32(10), sc_vec(vec![ScVal::U32(20), ScVal::U32(21)]), ScVal::U32(30)])),
+            (string("fees/2024"), sc_vec(vec![ScVal::U32(5)])),
+        ])
+    }
+
+    fn open<'a>(value: &'a ScVal, path: &str) -> Option<&'a ScVal> {
+        arguments::split_quoted(path, '/').unwrap().into_iter().try_fold(value, |value, segment| child(value, segment))
+    }
+
+    #[test]
+    fn child_opens_map_keys_and_indexes() {
+        let storage = storage();
+        assert_eq!(child(&storage, "Admin"), Some(&ScVal::U32(1)));
+        assert_eq!(child(&storage, "0"), Some(&ScVal::U32(1)));
+        assert_eq!(open(&storage, "Config/1/0"), Some(&ScVal::U32(20)));
+        assert_eq!(open(&storage, "Config/2"), Some(&ScVal::U32(30)));
+    }
+
+    #[test]
+    fn quoted_segments_open_keys_containing_slashes() {
+        let storage = storage();
+        assert_eq!(open(&storage, "\"fees/2024\"/0"), Some(&ScVal::U32(5)));
+        assert_eq!(child(&storage, "\"Admin\""), Some(&ScVal::U32(1)));
+        assert_eq!(open(&storage, "fees/2024"), None);
+    }
+
+    #[test]
+    fn missing_segments_open_nothing() {
+        let storage = storage();
+        assert_eq!(child(&storage, "Owner"), None);
+        assert_eq!(child(&storage, "3"), None);
+        assert_eq!(open(&storage, "Config/1/5"), None);
+        assert_eq!(open(&storage, "Admin/0"), None);
+    }
+
+    #[test]
+    fn summary_shows_container_sizes() {
+        let storage = storage();
+        assert_eq!(summary(&storage), "map of 3 ▸");
+        assert_eq!(summary(child(&storage, "Config").unwrap()), "vec of 3 ▸");
+        assert_eq!(summary(&ScVal::U32(7)), "7u32");
+        assert_eq!(summary(&string("a/b")), "\"a/b\"");
+    }
+}
@@ -0,0 +1,95 @@
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use stellar_xdr::curr::{ContractDataDurability, LedgerEntryData, LedgerKey, TrustLineAsset};
+
+use crate::archival::EntryStatus;
+use crate::network::StellarNetwork;
+use crate::rpc;
+use crate::scval;
+
+/// Fetches the ledger entry behind `key` and renders it decoded.
+pub fn render(network: StellarNetwork, key: &LedgerKey) -> anyhow::Result<Vec<Line<'static>>> {
+    let ledger_entries = rpc::get_ledger_entries(network, std::slice::from_ref(key))?;
+    let mut lines = vec![Line::from(format!("{} ledger entry on {network}", key.name()).bold())];
+
+    let Some(entry) = ledger_entries.entries.first() else {
+        let durability = match key {
+            LedgerKey::ContractData(data_key) => data_key.durability,
+            _ => ContractDataDurability::Persistent,
+        };
+        lines.push(Line::raw(format!("Key: {}", serde_json::to_string(key)?)));
+        lines.push(EntryStatus::NotFound.to_line(durability));
+        return Ok(lines);
+    };
+
+    let durability = match &entry.data {
+        LedgerEntryData::ContractData(data) => data.durability,
+        _ => ContractDataDurability::Persistent,
+    };
+    lines.push(Line::raw(format!("Key: {}", serde_json::to_string(&entry.key)?)));
+    lines.push(Line::raw(format!(
+        "Last modified ledger: {}, latest ledger: {}",
+        entry.last_modified_ledger, ledger_entries.latest_ledger
+    )));
+    lines.push(EntryStatus::of(entry, ledger_entries.latest_ledger).to_line(durability));
+    lines.push(Line::raw(""));
+    lines.extend(
+        serde_json::to_string_pretty(&entry.data)?
+            .lines()
+            .map(|line| Line::raw(line.to_string())),
+    );
+    Ok(lines)
+}
+
+/// One line summary of the entry a key addresses, e.g. `contract data CABC… persistent Balance`.
+pub fn describe_key(key: &LedgerKey) -> String {
+    match key {
+        LedgerKey::Account(account) => format!("account {}", account.account_id),
+        LedgerKey::Trustline(trustline) => {
+            format!("trustline {} {}", trustline.account_id, trustline_asset(&trustline.asset))
+        }
+        LedgerKey::ContractData(data) => {
+            format!("contract data {} {} {}", data.contract, data.durability, scval::format(&data.key))
+        }
+        LedgerKey::ContractCode(code) => format!("contract code {}", code.hash),
+        LedgerKey::Ttl(ttl) => format!("ttl {}", ttl.key_hash),
+        other => other.name().to_string(),
+    }
+}
+
+/// One line summary of an entry's data, like [`describe_key`] plus the interesting values.
+pub fn describe_data(data: &LedgerEntryData) -> String {
+    match data {
+        LedgerEntryData::Account(account) => format!(
+            "account {} balance {} seq {}",
+            account.account_id, account.balance, account.seq_num.0
+        ),
+        LedgerEntryData::Trustline(trustline) => format!(
+            "trustline {} {} balance {}",
+            trustline.account_id,
+            trustline_asset(&trustline.asset),
+            trustline.balance
+        ),
+        LedgerEntryData::ContractData(data) => format!(
+            "contract data {} {} {} = {}",
+            data.contract,
+            data.durability,
+            scval::format(&data.key),
+            scval::format(&data.val)
+        ),
+        LedgerEntryData::ContractCode(code) => {
+            format!("contract code {} ({} bytes)", code.hash, code.code.len())
+        }
+        LedgerEntryData::Ttl(ttl) => format!("ttl {} live until ledger {}", ttl.key_hash, ttl.live_until_ledger_seq),
+        other => other.name().to_string(),
+    }
+}
+
+fn trustline_asset(asset: &TrustLineAsset) -> String {
+    match asset {
+        TrustLineAsset::Native => "native".to_string(),
+        TrustLineAsset::CreditAlphanum4(asset) => format!("{}:{}", asset.asset_code, asset.issuer),
+        TrustLineAsset::CreditAlphanum12(asset) => format!("{}:{}", asset.asset_code, asset.issuer),
+        TrustLineAsset::PoolShare(pool_id) => format!("pool share {pool_id}"),
+    }
+}
@@ -0,0 +1,189 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context};
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{AccountId, AlphaNum12, AlphaNum4, AssetCode, ContractDataDurability, Hash, LedgerKey, LedgerKeyAccount, LedgerKeyContractCode, LedgerKeyContractData, LedgerKeyTrustLine, Limits, ReadXdr, ScAddress, ScSpecTypeDef, ScSymbol, ScVal, ScVec, TrustLineAsset, WriteXdr};
+
+use crate::commands::commands::ContractDataArgs;
+use crate::{arguments, scval};
+
+/// Durability values as accepted by the `stellar` CLI `--durability` option.
+pub const DURABILITY_OPTIONS: [&str; 2] = ["persistent", "temporary"];
+
+/// Ledger entry types the ledger entry explorer can build keys for, `xdr` takes a pasted key.
+pub const LEDGER_KEY_KIND_OPTIONS: [&str; 5] = ["xdr", "account", "trustline", "contract code", "contract data"];
+
+/// `ScVal` types the data key builder can produce.
+pub const KEY_TYPE_OPTIONS: [&str; 5] = ["symbol", "string", "u32", "address", "vec"];
+
+pub fn parse_durability(durability: &str) -> anyhow::Result<ContractDataDurability> {
+    match durability {
+        "persistent" => Ok(ContractDataDurability::Persistent),
+        "temporary" => Ok(ContractDataDurability::Temporary),
+        other => Err(anyhow!("unknown durability `{other}`")),
+    }
+}
+
+pub fn parse_address(address: &str) -> anyhow::Result<ScAddress> {
+    ScAddress::from_str(address).map_err(|_| anyhow!("invalid address `{address}`"))
+}
+
+/// Builds the `LedgerKey` of the contract data entry addressed by `args`.
+pub fn contract_data_key(args: &ContractDataArgs) -> anyhow::Result<LedgerKey> {
+    let key = if !args.key_xdr.is_empty() {
+        ScVal::from_xdr_base64(&args.key_xdr, Limits::none()).context("invalid key XDR")?
+    } else if !args.key.is_empty() {
+        ScVal::Symbol(ScSymbol(args.key.as_str().try_into().context("invalid symbol key")?))
+    } else {
+        ScVal::LedgerKeyContractInstance
+    };
+    // The instance entry is always persistent, whatever durability was picked.
+    let durability = if args.is_instance() {
+        ContractDataDurability::Persistent
+    } else {
+        parse_durability(&args.durability)?
+    };
+
+    Ok(LedgerKey::ContractData(LedgerKeyContractData {
+        contract: parse_address(&args.contract_id)?,
+        key,
+        durability,
+    }))
+}
+
+pub fn parse_account_id(account_id: &str) -> anyhow::Result<AccountId> {
+    AccountId::from_str(account_id).map_err(|_| anyhow!("invalid account `{account_id}`"))
+}
+
+pub fn ledger_key_from_xdr(xdr: &str) -> anyhow::Result<LedgerKey> {
+    LedgerKey::from_xdr_base64(xdr, Limits::none()).context("invalid LedgerKey XDR")
+}
+
+pub fn account_key(account_id: &str) -> anyhow::Result<LedgerKey> {
+    Ok(LedgerKey::Account(LedgerKeyAccount {
+        account_id: parse_account_id(account_id)?,
+    }))
+}
+
+/// Key of the trustline of `account_id` to `asset`, given as `CODE:ISSUER`.
+pub fn trustline_key(account_id: &str, asset: &str) -> anyhow::Result<LedgerKey> {
+    let Some((code, issuer)) = asset.split_once(':') else {
+        bail!("asset must be given as CODE:ISSUER");
+    };
+    let issuer = parse_account_id(issuer)?;
+    let asset = match AssetCode::from_str(code).map_err(|_| anyhow!("invalid asset code `{code}`"))? {
+        AssetCode::CreditAlphanum4(asset_code) => TrustLineAsset::CreditAlphanum4(AlphaNum4 { asset_code, issuer }),
+        AssetCode::CreditAlphanum12(asset_code) => TrustLineAsset::CreditAlphanum12(AlphaNum12 { asset_code, issuer }),
+    };
+    Ok(LedgerKey::Trustline(LedgerKeyTrustLine {
+        account_id: parse_account_id(account_id)?,
+        asset,
+    }))
+}
+
+/// Key of the contract code entry with the given hex encoded wasm hash.
+pub fn contract_code_key(wasm_hash: &str) -> anyhow::Result<LedgerKey> {
+    Ok(LedgerKey::ContractCode(LedgerKeyContractCode {
+        hash: Hash::from_str(wasm_hash).map_err(|_| anyhow!("invalid wasm hash `{wasm_hash}`"))?,
+    }))
+}
+
+/// Parses a key value typed as one of [`KEY_TYPE_OPTIONS`].
+///
+/// Vec values are JSON arrays of `type:value` strings, untyped strings are symbols and numbers
+/// are u32s, e.g. `["Balance", "address:GABC...", 7]`. Quoting keeps commas inside elements.
+pub fn parse_scval(key_type: &str, value: &str) -> anyhow::Result<ScVal> {
+    let value = value.trim();
+    Ok(match key_type {
+        "symbol" => arguments::parse_arg(&[], &ScSpecTypeDef::Symbol, value)?,
+        "string" => arguments::parse_arg(&[], &ScSpecTypeDef::String, value)?,
+        "u32" => arguments::parse_arg(&[], &ScSpecTypeDef::U32, value)?,
+        "address" => arguments::parse_arg(&[], &ScSpecTypeDef::Address, value)?,
+        "vec" => {
+            let elements: Vec<serde_json::Value> = serde_json::from_str(value)
+                .with_context(|| format!("vec keys are JSON arrays, e.g. [\"Balance\", \"address:G...\"], got `{value}`"))?;
+            let elements = elements.iter().map(parse_vec_element).collect::<anyhow::Result<Vec<_>>>()?;
+            ScVal::Vec(Some(ScVec(elements.try_into()?)))
+        }
+        other => bail!("unknown key type `{other}`"),
+    })
+}
+
+fn parse_vec_element(element: &serde_json::Value) -> anyhow::Result<ScVal> {
+    match element {
+        serde_json::Value::String(element) => match element.split_once(':') {
+            Some(("vec", _)) => bail!("nested vecs are not supported"),
+            Some((element_type, element_value)) if KEY_TYPE_OPTIONS.contains(&element_type) => {
+                parse_scval(element_type, element_value)
+            }
+            _ => parse_scval("symbol", element),
+        },
+        serde_json::Value::Number(number) => parse_scval("u32", &number.to_string()),
+        serde_json::Value::Array(_) => bail!("nested vecs are not supported"),
+        other => bail!("unsupported vec element `{other}`, use a `type:value` string"),
+    }
+}
+
+/// Renders a generated data key: its `ScVal`, the `LedgerKey` XDR with its hash and
+/// the value to pass as `--key-xdr`.
+pub fn render_data_key(args: &ContractDataArgs) -> anyhow::Result<Vec<Line<'static>>> {
+    let ledger_key = contract_data_key(args)?;
+    let LedgerKey::ContractData(LedgerKeyContractData { key, durability, .. }) = &ledger_key else {
+        bail!("not a contract data key");
+    };
+    let ledger_key_xdr = ledger_key.to_xdr(Limits::none())?;
+    let hash = scval::hex(&Sha256::digest(&ledger_key_xdr));
+
+    Ok(vec![
+        Line::from(format!("Data key of {} ({durability})", args.contract_id).bold()),
+        Line::raw(format!("Key ScVal:      {}", serde_json::to_string(key)?)),
+        Line::raw(format!("LedgerKey XDR:  {}", ledger_key.to_xdr_base64(Limits::none())?)),
+        Line::raw(format!("LedgerKey hash: {hash}")),
+        Line::raw(format!("--key-xdr:      {}", args.key_xdr)),
+        Line::from("The key is pre-filled as `--key-xdr` in the read, extend and restore scripts".dim()),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_xdr::curr::ScString;
+
+    use super::*;
+
+    fn symbol(value: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(value.try_into().unwrap()))
+    }
+
+    #[test]
+    fn vec_elements_keep_commas() {
+        let value = parse_scval("vec", r#"["Balance", "string:a, b", 7]"#).unwrap();
+        let expected = ScVal::Vec(Some(ScVec(
+            vec![symbol("Balance"), ScVal::String(ScString("a, b".try_into().unwrap())), ScVal::U32(7)]
+                .try_into()
+                .unwrap(),
+        )));
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn vec_element_types() {
+        let address = "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7";
+        let value = parse_scval("vec", &format!(r#"["symbol:Allowance", "address:{address}"]"#)).unwrap();
+        let expected = ScVal::Vec(Some(ScVec(
+            vec![symbol("Allowance"), ScVal::Address(parse_address(address).unwrap())]
+                .try_into()
+                .unwrap(),
+        )));
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn vec_rejects_unquoted_and_nested_values() {
+        assert!(parse_scval("vec", "Balance, Admin").is_err());
+        assert!(parse_scval("vec", r#"["Balance", ["Admin"]]"#).is_err());
+        assert!(parse_scval("vec", r#"["vec:[]"]"#).is_err());
+        assert!(parse_scval("vec", r#"["a, b"]"#).is_err());
+    }
+}
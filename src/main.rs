@@ -13,12 +13,39 @@ use crate::{
     tui::Tui,
 };
 
+mod account;
 pub mod app;
+mod archival;
+mod arguments;
+mod auth;
+mod bindings;
+mod build;
 mod commands;
+mod config_settings;
+mod diagnostics;
+mod envelope;
 pub mod event;
+mod events;
+mod footprint;
 pub mod handler;
+mod instance;
+mod ledger_entry;
+mod ledger_key;
+mod network;
+mod profile;
+mod rent;
+mod rpc;
+mod sac;
+mod sandbox;
+mod scval;
+mod simulation;
+mod spec;
+mod storage;
+mod token;
+mod transaction;
 pub mod tui;
 pub mod ui;
+mod wasm;
 
 #[tokio::main]
 async fn main() -> AppResult<()> {
@@ -0,0 +1,67 @@
+use strum::{Display, EnumIter, FromRepr};
+
+/// Base reserve in stroops of the public networks, every account and subentry locks this much.
+pub const BASE_RESERVE: u32 = 5_000_000;
+
+/// Stellar networks the explorer can target.
+///
+/// The display name matches the network alias used by the `stellar` CLI.
+#[derive(Default, Clone, Copy, Display, FromRepr, EnumIter, Debug, PartialEq, Eq, Hash)]
+pub enum StellarNetwork {
+    #[default]
+    #[strum(to_string = "local")]
+    Local,
+    #[strum(to_string = "testnet")]
+    Testnet,
+    #[strum(to_string = "futurenet")]
+    Futurenet,
+    #[strum(to_string = "mainnet")]
+    Mainnet,
+}
+
+impl StellarNetwork {
+    /// Get the next network, wrapping around to the first one.
+    pub fn next(self) -> Self {
+        let next_index = (self as usize).saturating_add(1);
+        Self::from_repr(next_index).unwrap_or_default()
+    }
+
+    /// Soroban RPC endpoint of the network.
+    pub fn rpc_url(self) -> &'static str {
+        match self {
+            StellarNetwork::Local => "http://localhost:8000/rpc",
+            StellarNetwork::Testnet => "https://soroban-testnet.stellar.org",
+            StellarNetwork::Futurenet => "https://rpc-futurenet.stellar.org",
+            StellarNetwork::Mainnet => "https://mainnet.sorobanrpc.com",
+        }
+    }
+
+    /// Horizon endpoint of the network, for the account data Soroban RPC can't list.
+    pub fn horizon_url(self) -> &'static str {
+        match self {
+            StellarNetwork::Local => "http://localhost:8000",
+            StellarNetwork::Testnet => "https://horizon-testnet.stellar.org",
+            StellarNetwork::Futurenet => "https://horizon-futurenet.stellar.org",
+            StellarNetwork::Mainnet => "https://horizon.stellar.org",
+        }
+    }
+
+    /// Default friendbot endpoint, mainnet accounts have to be funded for real.
+    pub fn friendbot_url(self) -> Option<&'static str> {
+        match self {
+            StellarNetwork::Local => Some("http://localhost:8000/friendbot"),
+            StellarNetwork::Testnet => Some("https://friendbot.stellar.org"),
+            StellarNetwork::Futurenet => Some("https://friendbot-futurenet.stellar.org"),
+            StellarNetwork::Mainnet => None,
+        }
+    }
+
+    pub fn network_passphrase(self) -> &'static str {
+        match self {
+            StellarNetwork::Local => "Standalone Network ; February 2017",
+            StellarNetwork::Testnet => "Test SDF Network ; September 2015",
+            StellarNetwork::Futurenet => "Test SDF Future Network ; October 2022",
+            StellarNetwork::Mainnet => "Public Global Stellar Network ; September 2015",
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use anyhow::bail;
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span};
+use serde_json::json;
+use stellar_xdr::curr::{
+    ContractEventBody, DiagnosticEvent, HostFunction, Limits, ReadXdr, ScVal, TransactionEnvelope,
+};
+
+use crate::network::StellarNetwork;
+use crate::simulation::{self, Simulation};
+use crate::transaction::{self, DecodedMeta};
+use crate::{rpc, scval, wasm};
+
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// Runs of a function shown in its sparkline.
+const SPARK_RUNS: usize = 16;
+/// CPU change between contract versions flagged as a regression or an improvement.
+const SIGNIFICANT_CHANGE_PERCENT: f64 = 5.0;
+
+/// Where the CPU instructions of a sample come from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CpuSource {
+    /// Instructions the invocation used, reported by the simulation or the node's metrics.
+    Measured,
+    /// The instruction limit the transaction declared, when nothing measured the invocation.
+    Declared,
+}
+
+/// Resources one invocation of a contract function used.
+#[derive(Clone, Debug)]
+pub struct CostSample {
+    pub contract_id: String,
+    pub function: String,
+    /// Wasm the contract ran, telling its versions apart.
+    pub wasm_hash: String,
+    pub cpu_insns: u64,
+    pub cpu_source: CpuSource,
+    /// Only known from simulations and from nodes emitting metrics events.
+    pub mem_bytes: Option<u64>,
+    pub read_bytes: u32,
+    pub write_bytes: u32,
+    /// Resource fee, the simulated minimum or the one charged after refunds. Inclusion fees are
+    /// left out, unknown when the meta doesn't split the charged fee.
+    pub resource_fee: Option<i64>,
+}
+
+impl CostSample {
+    /// Costs of a simulated invocation, `None` for other host functions.
+    pub fn simulated(network: StellarNetwork, envelope: &TransactionEnvelope, simulation: &Simulation) -> Option<Self> {
+        let (contract_id, function) = invoked_function(envelope)?;
+        let resources = &simulation.transaction_data.resources;
+        let (cpu_insns, cpu_source) = cpu(simulation.cpu_insns, resources.instructions);
+        Some(CostSample {
+            wasm_hash: version(network, &contract_id),
+            contract_id,
+            function,
+            cpu_insns,
+            cpu_source,
+            mem_bytes: simulation.mem_bytes,
+            read_bytes: resources.disk_read_bytes,
+            write_bytes: resources.write_bytes,
+            resource_fee: Some(simulation.min_resource_fee),
+        })
+    }
+
+    /// Costs of a submitted invocation, from its declared resources and the resource fee it was
+    /// charged.
+    ///
+    /// Actual CPU and memory use are taken from the `core_metrics` diagnostic events when the
+    /// node emits them.
+    pub fn submitted(network: StellarNetwork, hash: &str) -> anyhow::Result<Self> {
+        let result = rpc::request(network, "getTransaction", json!({ "hash": hash }))?;
+        if result["status"] != "SUCCESS" {
+            bail!("transaction {hash} did not succeed");
+        }
+        let envelope = TransactionEnvelope::from_xdr_base64(rpc::str_field(&result, "envelopeXdr")?, Limits::none())?;
+        let Some((contract_id, function)) = invoked_function(&envelope) else {
+            bail!("transaction {hash} does not invoke a contract function");
+        };
+        let Some(data) = simulation::transaction_data(&envelope) else {
+            bail!("transaction {hash} has no Soroban resources");
+        };
+        let mut meta = DecodedMeta::from_xdr(rpc::str_field(&result, "resultMetaXdr")?)?;
+        if meta.diagnostic_events.is_empty() {
+            meta.diagnostic_events = transaction::diagnostic_events_xdr(&result)?;
+        }
+        let (cpu_insns, cpu_source) = cpu(core_metric(&meta.diagnostic_events, "cpu_insn"), data.resources.instructions);
+        Ok(CostSample {
+            wasm_hash: version(network, &contract_id),
+            contract_id,
+            function,
+            cpu_insns,
+            cpu_source,
+            mem_bytes: core_metric(&meta.diagnostic_events, "mem_byte"),
+            read_bytes: data.resources.disk_read_bytes,
+            write_bytes: data.resources.write_bytes,
+            resource_fee: meta.resource_fee_charged,
+        })
+    }
+}
+
+/// Measured instructions when known, the declared instruction limit otherwise.
+fn cpu(measured: Option<u64>, declared: u32) -> (u64, CpuSource) {
+    match measured {
+        Some(cpu_insns) => (cpu_insns, CpuSource::Measured),
+        None => (u64::from(declared), CpuSource::Declared),
+    }
+}
+
+fn invoked_function(envelope: &TransactionEnvelope) -> Option<(String, String)> {
+    match &simulation::invoke_op(envelope).ok()?.host_function {
+        HostFunction::InvokeContract(call) => Some((
+            call.contract_address.to_string(),
+            call.function_name.to_utf8_string_lossy(),
+        )),
+        _ => None,
+    }
+}
+
+fn version(network: StellarNetwork, contract_id: &str) -> String {
+    wasm::deployed_hash(network, contract_id).unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn core_metric(events: &[DiagnosticEvent], name: &str) -> Option<u64> {
+    events.iter().find_map(|event| {
+        let ContractEventBody::V0(body) = &event.event.body;
+        let is_metric = body.topics.first().map(scval::format).as_deref() == Some("core_metrics")
+            && body.topics.get(1).map(scval::format).as_deref() == Some(name);
+        match &body.data {
+            ScVal::U64(value) if is_metric => Some(*value),
+            _ => None,
+        }
+    })
+}
+
+/// Renders the recorded costs per function and contract version, with the CPU change from the
+/// previous version and a sparkline of the function's recent runs.
+///
+/// Declared instruction limits only stand in for the CPU of versions without a measured run,
+/// marked with `*`, and are left out of the CPU change and the sparkline.
+pub fn render(samples: &[CostSample]) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(format!("Cost profile ({} invocations)", samples.len()).bold()),
+        Line::from(
+            format!(
+                "{:<20}{:<10}{:>5}{:>14}{:>12}{:>10}{:>10}{:>10}{:>9}  trend",
+                "Function", "Version", "Runs", "CPU insns", "Memory", "Read B", "Write B", "Res. fee", "CPU Δ"
+            )
+            .bold(),
+        ),
+    ];
+    if samples.is_empty() {
+        lines.push(Line::raw("No invocations recorded yet, invoke or simulate a contract function first"));
+    }
+
+    let mut declared_cpu = false;
+    let mut functions: Vec<(&str, &str)> = Vec::new();
+    for sample in samples {
+        let function = (sample.contract_id.as_str(), sample.function.as_str());
+        if !functions.contains(&function) {
+            functions.push(function);
+        }
+    }
+    for (contract_id, function) in functions {
+        let runs: Vec<&CostSample> = samples
+            .iter()
+            .filter(|sample| sample.contract_id == contract_id && sample.function == function)
+            .collect();
+        let cpu_history: Vec<u64> = runs
+            .iter()
+            .filter(|sample| sample.cpu_source == CpuSource::Measured)
+            .map(|sample| sample.cpu_insns)
+            .collect();
+        let trend = sparkline(&cpu_history[cpu_history.len().saturating_sub(SPARK_RUNS)..]);
+
+        let mut versions: Vec<&str> = Vec::new();
+        for run in &runs {
+            if !versions.contains(&run.wasm_hash.as_str()) {
+                versions.push(&run.wasm_hash);
+            }
+        }
+        let mut previous_cpu: Option<f64> = None;
+        for (index, version) in versions.iter().enumerate() {
+            let version_runs: Vec<&&CostSample> = runs.iter().filter(|run| run.wasm_hash == *version).collect();
+            let measured: Vec<f64> = version_runs
+                .iter()
+                .filter(|run| run.cpu_source == CpuSource::Measured)
+                .map(|run| run.cpu_insns as f64)
+                .collect();
+            let cpu = if measured.is_empty() {
+                declared_cpu = true;
+                format!("{:.0}*", mean(version_runs.iter().map(|run| run.cpu_insns as f64)))
+            } else {
+                format!("{:.0}", mean(measured.iter().copied()))
+            };
+            let memory = known_mean(version_runs.iter().filter_map(|run| run.mem_bytes.map(|mem_bytes| mem_bytes as f64)));
+            let fee = known_mean(version_runs.iter().filter_map(|run| run.resource_fee.map(|fee| fee as f64)));
+
+            let row = format!(
+                "{:<20}{:<10}{:>5}{:>14}{:>12}{:>10.0}{:>10.0}{:>10}",
+                if index == 0 { function } else { "" },
+                version.get(..8).unwrap_or(version),
+                version_runs.len(),
+                cpu,
+                memory,
+                mean(version_runs.iter().map(|run| f64::from(run.read_bytes))),
+                mean(version_runs.iter().map(|run| f64::from(run.write_bytes))),
+                fee,
+            );
+            let measured_cpu = (!measured.is_empty()).then(|| mean(measured.into_iter()));
+            let change = match (previous_cpu, measured_cpu) {
+                (Some(previous), Some(cpu)) if previous > 0.0 => {
+                    let percent = (cpu - previous) / previous * 100.0;
+                    let text = format!("{percent:>+8.1}%");
+                    if percent > SIGNIFICANT_CHANGE_PERCENT {
+                        text.red().bold()
+                    } else if percent < -SIGNIFICANT_CHANGE_PERCENT {
+                        text.green().bold()
+                    } else {
+                        text.into()
+                    }
+                }
+                _ => Span::raw(format!("{:>9}", "")),
+            };
+            let trend = if index == 0 { trend.clone() } else { String::new() };
+            lines.push(Line::from(vec![Span::raw(row), change, Span::raw(format!("  {trend}")).cyan()]));
+            if measured_cpu.is_some() {
+                previous_cpu = measured_cpu;
+            }
+        }
+    }
+    if declared_cpu {
+        lines.push(Line::from("* declared instruction limit, no run of this version reported its CPU use".dim()));
+    }
+    lines
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    if count == 0 { 0.0 } else { sum / f64::from(count) }
+}
+
+/// Mean of the values known for some runs, `-` when none is.
+fn known_mean(values: impl Iterator<Item = f64>) -> String {
+    let values: Vec<f64> = values.collect();
+    if values.is_empty() { "-".to_string() } else { format!("{:.0}", mean(values.into_iter())) }
+}
+
+fn sparkline(values: &[u64]) -> String {
+    let min = values.iter().copied().min().unwrap_or_default();
+    let max = values.iter().copied().max().unwrap_or_default();
+    values
+        .iter()
+        .map(|value| {
+            let level = if max == min { 0 } else { (value - min) * (SPARK_CHARS.len() as u64 - 1) / (max - min) };
+            SPARK_CHARS[level as usize]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(wasm_hash: &str, cpu_insns: u64, mem_bytes: Option<u64>, resource_fee: Option<i64>) -> CostSample {
+        CostSample {
+            contract_id: "CCONTRACT".to_string(),
+            function: "transfer".to_string(),
+            wasm_hash: wasm_hash.to_string(),
+            cpu_insns,
+            cpu_source: CpuSource::Measured,
+            mem_bytes,
+            read_bytes: 100,
+            write_bytes: 50,
+            resource_fee,
+        }
+    }
+
+    #[test]
+    fn sparkline_scales_between_min_and_max() {
+        assert_eq!(sparkline(&[10, 80, 45, 10]), "▁█▄▁");
+        assert_eq!(sparkline(&[5, 5, 5]), "▁▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn unknown_memory_and_fees_are_left_out_of_the_means() {
+        assert_eq!(known_mean([100.0, 300.0].into_iter()), "200");
+        assert_eq!(known_mean(std::iter::empty()), "-");
+
+        let samples = [sample("aaaaaaaa", 1_000, Some(400), Some(90)), sample("aaaaaaaa", 3_000, None, None)];
+        let row = render(&samples)[2].to_string();
+        assert!(row.contains(" 2000 "), "{row}");
+        assert!(row.contains(" 400 "), "{row}");
+        assert!(row.contains(" 90 "), "{row}");
+    }
+
+    #[test]
+    fn cpu_change_compares_consecutive_versions() {
+        let samples = [sample("aaaaaaaa", 1_000, None, None), sample("bbbbbbbb", 1_200, None, None)];
+        let lines = render(&samples);
+        assert!(lines[3].to_string().contains("+20.0%"), "{}", lines[3]);
+    }
+
+    #[test]
+    fn declared_cpu_is_marked_and_left_out_of_the_change() {
+        let declared = |wasm_hash, cpu_insns| CostSample { cpu_source: CpuSource::Declared, ..sample(wasm_hash, cpu_insns, None, None) };
+        assert_eq!(cpu(Some(900), 5_000), (900, CpuSource::Measured));
+        assert_eq!(cpu(None, 5_000), (5_000, CpuSource::Declared));
+
+        let samples = [
+            sample("aaaaaaaa", 1_000, None, None),
+            declared("aaaaaaaa", 9_000),
+            declared("bbbbbbbb", 5_000),
+            sample("cccccccc", 1_100, None, None),
+        ];
+        let lines = render(&samples);
+        let rows: Vec<String> = lines.iter().map(ToString::to_string).collect();
+        assert!(rows[2].contains(" 1000 ") && rows[2].ends_with("▁█"), "{rows:#?}");
+        assert!(rows[3].contains(" 5000* ") && !rows[3].contains('%'), "{rows:#?}");
+        assert!(rows[4].contains("+10.0%"), "{rows:#?}");
+        assert!(rows[5].starts_with("* declared instruction limit"), "{rows:#?}");
+    }
+}
@@ -0,0 +1,282 @@
+use anyhow::bail;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use stellar_xdr::curr::{ConfigSettingEntry, ConfigSettingId, ContractDataDurability};
+
+use crate::config_settings;
+use crate::network::StellarNetwork;
+
+/// Ledgers closed per day, assuming the target close time of 5 seconds.
+pub const LEDGERS_PER_DAY: u32 = 17_280;
+
+const DATA_SIZE_1KB_INCREMENT: i64 = 1024;
+/// Size of the TTL entry written alongside every extended entry.
+const TTL_ENTRY_SIZE: u32 = 48;
+/// Lower bound the host applies to the rent fee per 1KB.
+const MINIMUM_RENT_FEE_PER_1KB: i64 = 1000;
+const STROOPS_PER_XLM: f64 = 10_000_000.0;
+
+/// Rent parameters of a network, derived from its `ConfigSetting` ledger entries.
+#[derive(Clone, Debug, Default)]
+pub struct RentFeeConfig {
+    /// Rent fee per 1KB, computed from the current Soroban state size.
+    pub fee_per_rent_1kb: i64,
+    pub fee_per_write_1kb: i64,
+    pub fee_per_write_entry: i64,
+    pub persistent_rent_rate_denominator: i64,
+    pub temporary_rent_rate_denominator: i64,
+    pub max_entry_ttl: u32,
+    pub min_persistent_ttl: u32,
+    pub min_temporary_ttl: u32,
+}
+
+impl RentFeeConfig {
+    /// Fetches the config settings needed for rent calculation from `network`.
+    pub fn fetch(network: StellarNetwork) -> anyhow::Result<Self> {
+        let settings = config_settings::fetch(
+            network,
+            &[
+                ConfigSettingId::ContractLedgerCostV0,
+                ConfigSettingId::ContractLedgerCostExtV0,
+                ConfigSettingId::StateArchival,
+                ConfigSettingId::LiveSorobanStateSizeWindow,
+            ],
+        )?;
+        Self::from_settings(&settings)
+    }
+
+    pub fn from_settings(settings: &[ConfigSettingEntry]) -> anyhow::Result<Self> {
+        let mut config = RentFeeConfig::default();
+        let (mut ledger_cost, mut state_archival, mut state_size_window) = (None, None, None);
+
+        for setting in settings {
+            match setting {
+                ConfigSettingEntry::ContractLedgerCostV0(cost) => ledger_cost = Some(cost),
+                ConfigSettingEntry::ContractLedgerCostExtV0(cost_ext) => {
+                    config.fee_per_write_1kb = cost_ext.fee_write1_kb;
+                }
+                ConfigSettingEntry::StateArchival(archival) => state_archival = Some(archival),
+                ConfigSettingEntry::LiveSorobanStateSizeWindow(window) => state_size_window = Some(window),
+                _ => {}
+            }
+        }
+
+        let (Some(ledger_cost), Some(state_archival)) = (ledger_cost, state_archival) else {
+            bail!("network did not return the ledger cost and state archival settings");
+        };
+
+        config.fee_per_write_entry = ledger_cost.fee_write_ledger_entry;
+        config.persistent_rent_rate_denominator = state_archival.persistent_rent_rate_denominator;
+        config.temporary_rent_rate_denominator = state_archival.temp_rent_rate_denominator;
+        config.max_entry_ttl = state_archival.max_entry_ttl;
+        config.min_persistent_ttl = state_archival.min_persistent_ttl;
+        config.min_temporary_ttl = state_archival.min_temporary_ttl;
+
+        // The rent fee scales with the average Soroban state size over the sampling window.
+        let state_size = state_size_window
+            .filter(|window| !window.is_empty())
+            .map(|window| window.iter().sum::<u64>() / window.len() as u64)
+            .unwrap_or_default();
+        let state_size = i64::try_from(state_size).unwrap_or(i64::MAX);
+        let target = ledger_cost.soroban_state_target_size_bytes.max(1);
+        let low = ledger_cost.rent_fee1_kb_soroban_state_size_low;
+        let high = ledger_cost.rent_fee1_kb_soroban_state_size_high;
+        let fee_rate_multiplier = high.saturating_sub(low).max(0);
+
+        config.fee_per_rent_1kb = if state_size < target {
+            div_ceil(fee_rate_multiplier.saturating_mul(state_size), target).saturating_add(low)
+        } else {
+            let growth_factor = i64::from(ledger_cost.soroban_state_rent_fee_growth_factor);
+            let post_target_fee = div_ceil(
+                fee_rate_multiplier
+                    .saturating_mul(state_size - target)
+                    .saturating_mul(growth_factor),
+                target,
+            );
+            high.saturating_add(post_target_fee)
+        }
+        .max(MINIMUM_RENT_FEE_PER_1KB);
+
+        Ok(config)
+    }
+
+    /// Rent fee in stroops for extending an entry of `entry_size` bytes by `ledgers` ledgers.
+    ///
+    /// Mirrors the host's rent computation for a single TTL extension, including the
+    /// write of the TTL entry itself.
+    pub fn extension_fee(&self, durability: ContractDataDurability, entry_size: u32, ledgers: u32) -> i64 {
+        let rent_rate_denominator = match durability {
+            ContractDataDurability::Persistent => self.persistent_rent_rate_denominator,
+            ContractDataDurability::Temporary => self.temporary_rent_rate_denominator,
+        };
+        let rent_fee = div_ceil(
+            i64::from(entry_size)
+                .saturating_mul(self.fee_per_rent_1kb)
+                .saturating_mul(i64::from(ledgers)),
+            DATA_SIZE_1KB_INCREMENT.saturating_mul(rent_rate_denominator),
+        );
+        let ttl_write_fee = div_ceil(
+            i64::from(TTL_ENTRY_SIZE).saturating_mul(self.fee_per_write_1kb),
+            DATA_SIZE_1KB_INCREMENT,
+        );
+
+        rent_fee
+            .saturating_add(self.fee_per_write_entry)
+            .saturating_add(ttl_write_fee)
+    }
+
+    /// Longest extension the network accepts, counted from the current ledger.
+    pub fn max_extension(&self) -> u32 {
+        self.max_entry_ttl.saturating_sub(1)
+    }
+}
+
+/// Renders a comparison of rent fees for the requested extension and common alternatives.
+pub fn compare_extensions(
+    network: StellarNetwork,
+    durability: ContractDataDurability,
+    entry_size: u32,
+    requested_ledgers: u32,
+) -> anyhow::Result<Vec<Line<'static>>> {
+    let config = RentFeeConfig::fetch(network)?;
+    let max_extension = config.max_extension();
+
+    let options = [
+        (format!("requested ({requested_ledgers} ledgers)"), requested_ledgers),
+        ("extend 30 days".to_string(), 30 * LEDGERS_PER_DAY),
+        ("extend 90 days".to_string(), 90 * LEDGERS_PER_DAY),
+        ("extend 180 days".to_string(), 180 * LEDGERS_PER_DAY),
+        ("extend 1 year".to_string(), 365 * LEDGERS_PER_DAY),
+        ("max entry TTL".to_string(), max_extension),
+    ];
+
+    let mut lines = vec![
+        Line::from(format!("Rent fee estimate on {network}").bold()),
+        Line::raw(format!(
+            "{entry_size} byte {durability} entry, rent fee per 1KB: {} stroops, max entry TTL: {} ledgers",
+            config.fee_per_rent_1kb, config.max_entry_ttl
+        )),
+        Line::raw(format!(
+            "Minimum TTL: {} ledgers persistent, {} ledgers temporary",
+            config.min_persistent_ttl, config.min_temporary_ttl
+        )),
+        Line::raw(""),
+        Line::from(format!("{:<28}{:>12}{:>10}{:>16}{:>16}", "Option", "Ledgers", "Days", "Fee (stroops)", "Fee (XLM)").bold()),
+    ];
+
+    for (label, ledgers) in options {
+        let capped = ledgers.min(max_extension);
+        let fee = config.extension_fee(durability, entry_size, capped);
+        let row = format!(
+            "{label:<28}{capped:>12}{:>10.1}{fee:>16}{:>16.7}",
+            f64::from(capped) / f64::from(LEDGERS_PER_DAY),
+            fee as f64 / STROOPS_PER_XLM,
+        );
+        lines.push(if capped < ledgers {
+            Line::from(format!("{row}  (capped at max TTL)").yellow())
+        } else {
+            Line::raw(row)
+        });
+    }
+
+    lines.push(Line::raw(""));
+    lines.push(Line::from(
+        "Fees assume the entry is about to expire; resource and inclusion fees are not included.".dim(),
+    ));
+    Ok(lines)
+}
+
+fn div_ceil(numerator: i64, denominator: i64) -> i64 {
+    if denominator <= 0 {
+        return numerator;
+    }
+    let quotient = numerator / denominator;
+    if numerator % denominator > 0 {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_xdr::curr::{ConfigSettingContractLedgerCostExtV0, ConfigSettingContractLedgerCostV0, StateArchivalSettings};
+
+    use super::*;
+
+    fn settings(state_size_window: &[u64]) -> Vec<ConfigSettingEntry> {
+        vec![
+            ConfigSettingEntry::ContractLedgerCostV0(ConfigSettingContractLedgerCostV0 {
+                fee_write_ledger_entry: 300,
+                soroban_state_target_size_bytes: 1_000_000,
+                rent_fee1_kb_soroban_state_size_low: 1_000,
+                rent_fee1_kb_soroban_state_size_high: 10_000,
+                soroban_state_rent_fee_growth_factor: 5,
+                ..Default::default()
+            }),
+            ConfigSettingEntry::ContractLedgerCostExtV0(ConfigSettingContractLedgerCostExtV0 {
+                fee_write1_kb: 2_000,
+                ..Default::default()
+            }),
+            ConfigSettingEntry::StateArchival(StateArchivalSettings {
+                max_entry_ttl: 3_110_400,
+                min_temporary_ttl: 17_280,
+                min_persistent_ttl: 2_073_600,
+                persistent_rent_rate_denominator: 2_103,
+                temp_rent_rate_denominator: 4_206,
+                ..Default::default()
+            }),
+            ConfigSettingEntry::LiveSorobanStateSizeWindow(state_size_window.to_vec().try_into().unwrap()),
+        ]
+    }
+
+    #[test]
+    fn rent_fee_interpolates_below_target_size() {
+        let config = RentFeeConfig::from_settings(&settings(&[400_000, 600_000])).unwrap();
+        // 9000 * 500_000 / 1_000_000 above the low fee.
+        assert_eq!(config.fee_per_rent_1kb, 5_500);
+        assert_eq!(config.fee_per_write_1kb, 2_000);
+        assert_eq!(config.fee_per_write_entry, 300);
+        assert_eq!(config.max_extension(), 3_110_399);
+    }
+
+    #[test]
+    fn rent_fee_grows_past_target_size() {
+        let config = RentFeeConfig::from_settings(&settings(&[1_500_000])).unwrap();
+        // 9000 * 500_000 * 5 / 1_000_000 above the high fee.
+        assert_eq!(config.fee_per_rent_1kb, 32_500);
+    }
+
+    #[test]
+    fn rent_fee_has_a_floor() {
+        let mut settings = settings(&[]);
+        if let ConfigSettingEntry::ContractLedgerCostV0(cost) = &mut settings[0] {
+            cost.rent_fee1_kb_soroban_state_size_low = 100;
+            cost.rent_fee1_kb_soroban_state_size_high = 200;
+        }
+        let config = RentFeeConfig::from_settings(&settings).unwrap();
+        assert_eq!(config.fee_per_rent_1kb, MINIMUM_RENT_FEE_PER_1KB);
+    }
+
+    #[test]
+    fn missing_settings_are_an_error() {
+        assert!(RentFeeConfig::from_settings(&settings(&[])[1..]).is_err());
+    }
+
+    #[test]
+    fn extension_fee_adds_rent_and_ttl_write() {
+        let config = RentFeeConfig::from_settings(&settings(&[500_000])).unwrap();
+        // rent ceil(1024 * 5500 * 1000 / (1024 * 2103)) = 2616, entry write 300,
+        // TTL write ceil(48 * 2000 / 1024) = 94.
+        assert_eq!(config.extension_fee(ContractDataDurability::Persistent, 1_024, 1_000), 3_010);
+        assert_eq!(config.extension_fee(ContractDataDurability::Temporary, 1_024, 1_000), 1_702);
+        assert_eq!(config.extension_fee(ContractDataDurability::Persistent, 1_024, 0), 394);
+    }
+
+    #[test]
+    fn div_ceil_rounds_up() {
+        assert_eq!(div_ceil(6, 2), 3);
+        assert_eq!(div_ceil(7, 2), 4);
+        assert_eq!(div_ceil(5, 0), 5);
+    }
+}
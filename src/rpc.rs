@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use serde_json::{json, Value};
+use stellar_xdr::curr::{LedgerEntryData, LedgerEntryExt, LedgerKey, Limits, ReadXdr, WriteXdr};
+use xshell::{cmd, Shell};
+
+use crate::network::StellarNetwork;
+
+/// A ledger entry returned by `getLedgerEntries`.
+#[derive(Clone, Debug)]
+pub struct LedgerEntryResult {
+    pub key: LedgerKey,
+    pub data: LedgerEntryData,
+    pub last_modified_ledger: u32,
+    /// Only present for contract data and contract code entries.
+    pub live_until_ledger: Option<u32>,
+    /// Sponsorship extension of the entry. Servers before protocol 23 don't report it, their
+    /// entries read as unsponsored.
+    pub ext: LedgerEntryExt,
+}
+
+#[derive(Clone, Debug)]
+pub struct LedgerEntries {
+    pub entries: Vec<LedgerEntryResult>,
+    pub latest_ledger: u32,
+}
+
+/// Sends a JSON-RPC request to the Soroban RPC server of `network` and returns its `result`.
+///
+/// Requests go through `curl` so the explorer shares the shell environment (proxies,
+/// certificates) used by the `stellar` CLI.
+pub fn request(network: StellarNetwork, method: &str, params: Value) -> anyhow::Result<Value> {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    })
+    .to_string();
+    let rpc_url = network.rpc_url();
+    let header = "Content-Type: application/json";
+
+    let sh = Shell::new()?;
+    let raw = cmd!(sh, "curl --silent --show-error -X POST -H {header} --data {body} {rpc_url}")
+        .timeout(Duration::from_secs(10))
+        .read()
+        .with_context(|| format!("{method} request to {rpc_url} failed"))?;
+
+    let mut response: Value = serde_json::from_str(&raw)
+        .with_context(|| format!("{method} returned an invalid response"))?;
+    if let Some(error) = response.get("error") {
+        let message = error["message"].as_str().unwrap_or("unknown error");
+        return Err(anyhow!("{method} failed: {message}"));
+    }
+    Ok(response["result"].take())
+}
+
+/// Fetches the given ledger entries with `getLedgerEntries`.
+///
+/// Keys that don't exist on the ledger are silently left out of the result.
+pub fn get_ledger_entries(network: StellarNetwork, keys: &[LedgerKey]) -> anyhow::Result<LedgerEntries> {
+    let keys = keys
+        .iter()
+        .map(|key| key.to_xdr_base64(Limits::none()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let result = request(network, "getLedgerEntries", json!({ "keys": keys }))?;
+
+    let entries = result["entries"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|entry| {
+            Ok(LedgerEntryResult {
+                key: LedgerKey::from_xdr_base64(str_field(entry, "key")?, Limits::none())?,
+                data: LedgerEntryData::from_xdr_base64(str_field(entry, "xdr")?, Limits::none())?,
+                last_modified_ledger: u32_field(entry, "lastModifiedLedgerSeq").unwrap_or_default(),
+                live_until_ledger: u32_field(entry, "liveUntilLedgerSeq"),
+                ext: match entry["extXdr"].as_str() {
+                    Some(ext) => LedgerEntryExt::from_xdr_base64(ext, Limits::none())?,
+                    None => LedgerEntryExt::V0,
+                },
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(LedgerEntries {
+        entries,
+        latest_ledger: u32_field(&result, "latestLedger").unwrap_or_default(),
+    })
+}
+
+pub(crate) fn str_field<'v>(value: &'v Value, field: &str) -> anyhow::Result<&'v str> {
+    value[field]
+        .as_str()
+        .ok_or_else(|| anyhow!("response is missing `{field}`"))
+}
+
+/// RPC servers encode some numbers as strings, so accept both.
+pub(crate) fn u32_field(value: &Value, field: &str) -> Option<u32> {
+    match &value[field] {
+        Value::Number(number) => number.as_u64().and_then(|n| u32::try_from(n).ok()),
+        Value::String(string) => string.parse().ok(),
+        _ => None,
+    }
+}
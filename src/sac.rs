@@ -0,0 +1,71 @@
+use anyhow::bail;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+
+use crate::commands::commands::StellarCliCmdName::{AssetContractId, ContractInvoke};
+use crate::commands::commands::{execute, ContractDataArgs, InvokeArgs};
+use crate::network::StellarNetwork;
+use crate::{ledger_key, rpc};
+
+/// Resolves the Stellar Asset Contract ID of a `CODE:ISSUER` or `native` asset.
+pub fn contract_id(network: StellarNetwork, asset: &str) -> anyhow::Result<String> {
+    let res = execute(AssetContractId(network, asset.to_string()));
+    if !res.success {
+        bail!("could not resolve the contract of {asset}: {}", res.stderr);
+    }
+    Ok(res.result.trim().to_string())
+}
+
+/// Whether the contract has an instance on the ledger.
+pub fn is_deployed(network: StellarNetwork, contract_id: &str) -> anyhow::Result<bool> {
+    let key = ledger_key::contract_data_key(&ContractDataArgs {
+        network,
+        contract_id: contract_id.to_string(),
+        ..Default::default()
+    })?;
+    Ok(!rpc::get_ledger_entries(network, &[key])?.entries.is_empty())
+}
+
+/// Renders the token metadata of an asset contract and the balances of `addresses`.
+///
+/// Values are read with simulated invocations, so nothing is submitted.
+pub fn render_info(
+    network: StellarNetwork,
+    contract_id: &str,
+    source_account: &str,
+    addresses: &[&str],
+) -> Vec<Line<'static>> {
+    let read = |function: &str, args: String| {
+        let res = execute(ContractInvoke(InvokeArgs {
+            network,
+            contract_id: contract_id.to_string(),
+            source_account: source_account.to_string(),
+            function: function.to_string(),
+            args,
+            build_only: false,
+        }));
+        if res.success {
+            Line::raw(res.result.trim().to_string())
+        } else {
+            let error = res.stderr.lines().find(|line| line.contains("Error")).unwrap_or(res.stderr.trim());
+            Line::from(error.to_string().red())
+        }
+    };
+
+    let mut lines = Vec::new();
+    for function in ["name", "symbol", "decimals", "admin"] {
+        let mut line = read(function, String::new());
+        line.spans.insert(0, format!("{function:<10}").into());
+        lines.push(line);
+    }
+    if !addresses.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::from("Balances".bold()));
+    }
+    for address in addresses {
+        let mut line = read("balance", format!("--id {address}"));
+        line.spans.insert(0, format!("{address}  ").into());
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,500 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context};
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{
+    AccountId, ContractDataDurability, ContractEvent, ContractExecutable, DiagnosticEvent, HostFunction,
+    InvokeContractArgs, LedgerEntry, LedgerEntryData, LedgerKey, LedgerKeyContractCode,
+    LedgerKeyContractData, PublicKey, ScAddress, ScError, ScSpecEntry, ScVal, SorobanAuthorizationEntry,
+    SorobanResources, Uint256,
+};
+
+use crate::commands::commands::InvokeArgs;
+use crate::events::format_contract_event;
+use crate::network::{StellarNetwork, BASE_RESERVE};
+use crate::rent::RentFeeConfig;
+use crate::{account, arguments, auth, ledger_entry, ledger_key, rpc, scval, simulation, spec, wasm};
+
+/// Diagnostic events shown when an invocation fails, the last ones carry the error.
+const DIAGNOSTIC_EVENTS_SHOWN: usize = 10;
+
+/// Ledger state the sandbox runs against, in the JSON layout of `stellar snapshot create`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Snapshot {
+    pub protocol_version: u32,
+    pub sequence_number: u32,
+    pub timestamp: u64,
+    /// Hex encoded hash of the network passphrase.
+    pub network_id: String,
+    pub base_reserve: u32,
+    pub min_persistent_entry_ttl: u32,
+    pub min_temp_entry_ttl: u32,
+    pub max_entry_ttl: u32,
+    #[serde(deserialize_with = "deserialize_entries", serialize_with = "serialize_entries")]
+    pub ledger_entries: Vec<SnapshotEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct SnapshotEntry {
+    pub entry: LedgerEntry,
+    pub live_until: Option<u32>,
+}
+
+/// Entries are read in both layouts of the CLI: `[key, [entry, live_until]]` pairs, and the
+/// `{entry, live_until}` objects of newer versions.
+fn deserialize_entries<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<SnapshotEntry>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entries {
+        Current(Vec<SnapshotEntry>),
+        Keyed(Vec<(LedgerKey, (LedgerEntry, Option<u32>))>),
+    }
+
+    Ok(match Entries::deserialize(deserializer)? {
+        Entries::Current(entries) => entries,
+        Entries::Keyed(entries) => entries
+            .into_iter()
+            .map(|(_, (entry, live_until))| SnapshotEntry { entry, live_until })
+            .collect(),
+    })
+}
+
+/// Entries are written as `[key, [entry, live_until]]` pairs, the layout every CLI version reads.
+fn serialize_entries<S: Serializer>(entries: &[SnapshotEntry], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(entries.iter().map(|entry| (entry.entry.to_key(), (&entry.entry, entry.live_until))))
+}
+
+impl Snapshot {
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let json = std::fs::read_to_string(path).with_context(|| format!("could not read {path}"))?;
+        serde_json::from_str(&json).with_context(|| format!("{path} is not a ledger snapshot"))
+    }
+
+    pub fn save(&self, path: &str) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?).with_context(|| format!("could not write {path}"))
+    }
+
+    pub fn entry(&self, key: &LedgerKey) -> Option<&SnapshotEntry> {
+        self.ledger_entries.iter().find(|entry| entry.entry.to_key() == *key)
+    }
+
+    /// Inserts the entry, replacing the one stored under the same key.
+    fn upsert(&mut self, entry: LedgerEntry, live_until: Option<u32>) {
+        let key = entry.to_key();
+        self.ledger_entries.retain(|existing| existing.entry.to_key() != key);
+        self.ledger_entries.push(SnapshotEntry { entry, live_until });
+    }
+
+    /// Fetches the entries behind `keys` into the snapshot and moves it to the latest ledger,
+    /// returns how many exist.
+    ///
+    /// Entries already in the snapshot are kept, refreshed when they are fetched again.
+    pub fn fetch(&mut self, network: StellarNetwork, keys: &[LedgerKey]) -> anyhow::Result<usize> {
+        let ledger_entries = rpc::get_ledger_entries(network, keys)?;
+        set_ledger_info(self, network, ledger_entries.latest_ledger)?;
+        for entry in &ledger_entries.entries {
+            let ledger_entry = LedgerEntry {
+                last_modified_ledger_seq: entry.last_modified_ledger,
+                data: entry.data.clone(),
+                ext: entry.ext.clone(),
+            };
+            self.upsert(ledger_entry, entry.live_until_ledger);
+        }
+        Ok(ledger_entries.entries.len())
+    }
+
+    /// Interface of a contract in the snapshot, read from its wasm.
+    fn contract_spec(&self, contract: &ScAddress) -> anyhow::Result<Vec<ScSpecEntry>> {
+        let instance_key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract: contract.clone(),
+            key: ScVal::LedgerKeyContractInstance,
+            durability: ContractDataDurability::Persistent,
+        });
+        let Some(LedgerEntryData::ContractData(instance)) = self.entry(&instance_key).map(|entry| &entry.entry.data)
+        else {
+            bail!("the snapshot has no instance of {contract}, capture an invocation of it first");
+        };
+        let ScVal::ContractInstance(instance) = &instance.val else {
+            bail!("the instance entry of {contract} holds no contract instance");
+        };
+        let ContractExecutable::Wasm(hash) = &instance.executable else {
+            bail!("{contract} is a stellar asset contract, its interface is not in the snapshot");
+        };
+        let code_key = LedgerKey::ContractCode(LedgerKeyContractCode { hash: hash.clone() });
+        let Some(LedgerEntryData::ContractCode(code)) = self.entry(&code_key).map(|entry| &entry.entry.data) else {
+            bail!("the snapshot has no wasm {hash} for {contract}");
+        };
+        wasm::decode_section(&code.code, wasm::SPEC_SECTION)
+    }
+}
+
+/// Captures the ledger entries an invocation reads and writes into the snapshot at `path`,
+/// creating it if needed, so the invocation can run offline afterwards.
+///
+/// Entries captured earlier are kept, refreshed when the invocation reads them again.
+pub fn capture(args: &InvokeArgs, path: &str) -> anyhow::Result<Vec<Line<'static>>> {
+    let (envelope, _) = simulation::simulate_invoke(args)?;
+    let Some(data) = simulation::transaction_data(&envelope) else {
+        bail!("the simulation returned no footprint");
+    };
+    let footprint = &data.resources.footprint;
+    let keys: Vec<LedgerKey> = footprint.read_only.iter().chain(footprint.read_write.iter()).cloned().collect();
+
+    let mut snapshot = if Path::new(path).is_file() { Snapshot::load(path)? } else { Snapshot::default() };
+    let found = snapshot.fetch(args.network, &keys)?;
+    snapshot.save(path)?;
+
+    Ok(vec![
+        Line::from(format!("Captured {} of {} on {} into {path}", args.function, args.contract_id, args.network).bold()),
+        Line::raw(format!(
+            "{} of {} footprint entries exist on the ledger, the snapshot holds {} entries at ledger {}",
+            found,
+            keys.len(),
+            snapshot.ledger_entries.len(),
+            snapshot.sequence_number
+        )),
+    ])
+}
+
+fn set_ledger_info(snapshot: &mut Snapshot, network: StellarNetwork, latest_ledger: u32) -> anyhow::Result<()> {
+    let latest = rpc::request(network, "getLatestLedger", json!({}))?;
+    let rent = RentFeeConfig::fetch(network)?;
+    // Servers without `closeTime` leave the sandbox running at the local time.
+    let close_time = match &latest["closeTime"] {
+        Value::String(time) => time.parse().ok(),
+        time => time.as_u64(),
+    };
+
+    snapshot.protocol_version = rpc::u32_field(&latest, "protocolVersion").unwrap_or(snapshot.protocol_version);
+    snapshot.sequence_number = latest_ledger;
+    snapshot.timestamp = close_time.unwrap_or_else(|| {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+    });
+    snapshot.network_id = scval::hex(&Sha256::digest(network.network_passphrase()));
+    snapshot.base_reserve = BASE_RESERVE;
+    snapshot.min_persistent_entry_ttl = rent.min_persistent_ttl;
+    snapshot.min_temp_entry_ttl = rent.min_temporary_ttl;
+    snapshot.max_entry_ttl = rent.max_entry_ttl;
+    Ok(())
+}
+
+/// A contract function call run in the sandbox.
+pub struct SandboxCall {
+    pub contract_id: String,
+    pub function: String,
+    /// Arguments as typed for `contract invoke`, parsed offline with the contract's interface.
+    pub args: String,
+    /// Address or identity the invocation runs as, an unfunded placeholder when empty.
+    pub source_account: String,
+}
+
+/// What the host returned for a sandboxed invocation.
+struct Outcome {
+    result: Result<ScVal, ScError>,
+    cpu_insns: u64,
+    mem_bytes: u64,
+    resources: SorobanResources,
+    events: Vec<ContractEvent>,
+    diagnostic_events: Vec<DiagnosticEvent>,
+    auth: Vec<SorobanAuthorizationEntry>,
+    changes: Vec<StorageChange>,
+}
+
+/// An entry the invocation touched.
+struct StorageChange {
+    key: LedgerKey,
+    read_only: bool,
+    /// New value of a read-write entry, `None` when the invocation removed it.
+    new_entry: Option<LedgerEntry>,
+    /// Live until ledger before and after the invocation.
+    live_until: Option<(u32, u32)>,
+}
+
+/// Runs a contract function against the snapshot with the Soroban host, offline, and renders
+/// its return value, events, storage changes and budget.
+///
+/// With `apply` the storage changes are written into `snapshot`, so later runs see them.
+pub fn run(snapshot: &mut Snapshot, call: &SandboxCall, apply: bool) -> anyhow::Result<Vec<Line<'static>>> {
+    let contract = ledger_key::parse_address(&call.contract_id)?;
+    let contract_spec = snapshot.contract_spec(&contract)?;
+    let Some(function) = spec::function(&contract_spec, &call.function) else {
+        bail!("{} has no function `{}`", call.contract_id, call.function);
+    };
+    let host_function = HostFunction::InvokeContract(InvokeContractArgs {
+        contract_address: contract,
+        function_name: function.name.clone(),
+        args: arguments::parse_args(&contract_spec, function, &call.args)?.try_into()?,
+    });
+    let source = if call.source_account.is_empty() {
+        AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([0; 32])))
+    } else {
+        AccountId::from_str(&account::resolve(&call.source_account)?)?
+    };
+
+    let outcome = host::invoke(snapshot, &host_function, &source)?;
+    let mut lines = vec![Line::from(
+        format!(
+            "{} of {} in the sandbox at ledger {}",
+            call.function, call.contract_id, snapshot.sequence_number
+        )
+        .bold(),
+    )];
+    match &outcome.result {
+        Ok(value) => lines.push(Line::from(format!("Returned {}", scval::format(value)).green())),
+        Err(error) => {
+            lines.push(Line::from(format!("Failed with {}", scval::format_error(error)).red().bold()));
+            if let ScError::Contract(code) = error {
+                for (enum_name, case) in spec::error_cases(&contract_spec, *code) {
+                    lines.push(Line::raw(format!("  {enum_name}::{}", case.name.to_utf8_string_lossy())));
+                }
+            }
+        }
+    }
+    lines.push(Line::raw(format!(
+        "Budget: {} CPU instructions, {} bytes of memory",
+        outcome.cpu_insns, outcome.mem_bytes
+    )));
+    lines.push(Line::raw(format!(
+        "Resources: {} read-only, {} read-write entries, {} bytes read, {} bytes written",
+        outcome.resources.footprint.read_only.len(),
+        outcome.resources.footprint.read_write.len(),
+        outcome.resources.disk_read_bytes,
+        outcome.resources.write_bytes
+    )));
+
+    lines.push(Line::from(format!("Events ({})", outcome.events.len()).bold()));
+    lines.extend(outcome.events.iter().map(format_contract_event));
+    if outcome.result.is_err() {
+        let skipped = outcome.diagnostic_events.len().saturating_sub(DIAGNOSTIC_EVENTS_SHOWN);
+        lines.push(Line::from("Diagnostic events".bold()));
+        lines.extend(outcome.diagnostic_events[skipped..].iter().map(|event| format_contract_event(&event.event)));
+    }
+
+    lines.extend(render_changes(snapshot, &outcome.changes));
+    lines.extend(auth::render(&outcome.auth));
+
+    if apply && outcome.result.is_ok() {
+        for change in outcome.changes {
+            let live_until = change.live_until.map(|(_, new)| new);
+            match change.new_entry {
+                Some(entry) => snapshot.upsert(entry, live_until),
+                None if change.read_only => {
+                    if let Some(existing) = snapshot.ledger_entries.iter_mut().find(|entry| entry.entry.to_key() == change.key) {
+                        existing.live_until = live_until.or(existing.live_until);
+                    }
+                }
+                None => snapshot.ledger_entries.retain(|entry| entry.entry.to_key() != change.key),
+            }
+        }
+    }
+    Ok(lines)
+}
+
+fn render_changes(snapshot: &Snapshot, changes: &[StorageChange]) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut changed = 0;
+    for change in changes {
+        let before = snapshot.entry(&change.key).map(|entry| &entry.entry.data);
+        let mut entry_lines = match (before, &change.new_entry) {
+            _ if change.read_only => vec![],
+            (None, Some(after)) => vec![Line::from(format!("+ {}", ledger_entry::describe_data(&after.data)).green())],
+            (Some(_), None) => vec![Line::from(format!("- {}", ledger_entry::describe_key(&change.key)).red())],
+            (Some(before), Some(after)) if *before != after.data => vec![
+                Line::from(format!("~ {}", ledger_entry::describe_data(&after.data)).yellow()),
+                Line::from(format!("    was {}", ledger_entry::describe_data(before)).dim()),
+            ],
+            _ => vec![],
+        };
+        if let Some((old, new)) = change.live_until.filter(|(old, new)| old != new) {
+            if entry_lines.is_empty() {
+                entry_lines.push(Line::raw(format!("  {}", ledger_entry::describe_key(&change.key))));
+            }
+            let ttl = if old == 0 { format!("live until ledger {new}") } else { format!("live until ledger {old} → {new}") };
+            entry_lines.push(Line::from(format!("    {ttl}").dim()));
+        }
+        if !entry_lines.is_empty() {
+            changed += 1;
+            lines.extend(entry_lines);
+        }
+    }
+
+    let mut header = vec![Line::from(format!("Storage changes ({changed})").bold())];
+    if changed == 0 {
+        header.push(Line::raw("The invocation changed no storage"));
+    }
+    header.extend(lines);
+    header
+}
+
+#[cfg(feature = "sandbox")]
+mod host {
+    use std::rc::Rc;
+
+    use anyhow::anyhow;
+    use soroban_env_host::budget::Budget;
+    use soroban_env_host::e2e_invoke::{invoke_host_function_in_recording_mode, RecordingInvocationAuthMode};
+    use soroban_env_host::storage::{EntryWithLiveUntil, SnapshotSource};
+    use soroban_env_host::{HostError, LedgerInfo};
+    use stellar_xdr::curr::{AccountId, HostFunction, LedgerEntry, LedgerKey, Limits, ReadXdr, ScError, ScErrorCode};
+
+    use super::{Outcome, Snapshot, StorageChange};
+    use crate::scval;
+
+    /// Seed of the host's PRNG, fixed so sandboxed runs are reproducible.
+    const PRNG_SEED: [u8; 32] = [0; 32];
+
+    struct Entries(Vec<(LedgerKey, EntryWithLiveUntil)>);
+
+    impl SnapshotSource for Entries {
+        fn get(&self, key: &Rc<LedgerKey>) -> Result<Option<EntryWithLiveUntil>, HostError> {
+            Ok(self.0.iter().find(|(entry_key, _)| entry_key == key.as_ref()).map(|(_, entry)| entry.clone()))
+        }
+    }
+
+    /// Invokes the host function in recording mode, so the footprint and the authorizations are
+    /// recorded rather than enforced, like a simulation.
+    pub(super) fn invoke(snapshot: &Snapshot, host_function: &HostFunction, source: &AccountId) -> anyhow::Result<Outcome> {
+        let entries = snapshot
+            .ledger_entries
+            .iter()
+            .map(|entry| (entry.entry.to_key(), (Rc::new(entry.entry.clone()), entry.live_until)))
+            .collect();
+        let ledger_info = LedgerInfo {
+            protocol_version: snapshot.protocol_version,
+            sequence_number: snapshot.sequence_number,
+            timestamp: snapshot.timestamp,
+            network_id: scval::parse_hex(&snapshot.network_id)?
+                .try_into()
+                .map_err(|_| anyhow!("the snapshot's network id is not 32 bytes"))?,
+            base_reserve: snapshot.base_reserve,
+            min_temp_entry_ttl: snapshot.min_temp_entry_ttl,
+            min_persistent_entry_ttl: snapshot.min_persistent_entry_ttl,
+            max_entry_ttl: snapshot.max_entry_ttl,
+        };
+        let host_error = |error: HostError| anyhow!("the sandbox could not run the invocation: {error:?}");
+
+        let budget = Budget::default();
+        let mut diagnostic_events = Vec::new();
+        let result = invoke_host_function_in_recording_mode(
+            &budget,
+            true,
+            host_function,
+            source,
+            RecordingInvocationAuthMode::Recording(true),
+            ledger_info,
+            Rc::new(Entries(entries)),
+            PRNG_SEED,
+            &mut diagnostic_events,
+        )
+        .map_err(host_error)?;
+
+        let changes = result
+            .ledger_changes
+            .into_iter()
+            .map(|change| {
+                Ok(StorageChange {
+                    key: LedgerKey::from_xdr(&change.encoded_key, Limits::none())?,
+                    read_only: change.read_only,
+                    new_entry: change
+                        .encoded_new_value
+                        .map(|value| LedgerEntry::from_xdr(value, Limits::none()))
+                        .transpose()?,
+                    live_until: change
+                        .ttl_change
+                        .map(|ttl| (ttl.old_live_until_ledger, ttl.new_live_until_ledger)),
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Outcome {
+            result: result
+                .invoke_result
+                .map_err(|error| ScError::try_from(error.error).unwrap_or(ScError::Context(ScErrorCode::InternalError))),
+            cpu_insns: budget.get_cpu_insns_consumed().map_err(host_error)?,
+            mem_bytes: budget.get_mem_bytes_consumed().map_err(host_error)?,
+            resources: result.resources,
+            events: result.contract_events,
+            diagnostic_events,
+            auth: result.auth,
+            changes,
+        })
+    }
+}
+
+#[cfg(not(feature = "sandbox"))]
+mod host {
+    use anyhow::bail;
+    use stellar_xdr::curr::{AccountId, HostFunction};
+
+    use super::{Outcome, Snapshot};
+
+    pub(super) fn invoke(_snapshot: &Snapshot, _host_function: &HostFunction, _source: &AccountId) -> anyhow::Result<Outcome> {
+        bail!("the sandbox needs the Soroban host, build the explorer with `--features sandbox`")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_xdr::curr::{
+        AccountId, ContractDataEntry, Hash, LedgerEntryExt, LedgerEntryExtensionV1, LedgerEntryExtensionV1Ext,
+        PublicKey, SponsorshipDescriptor,
+    };
+
+    use super::*;
+
+    fn snapshot() -> Snapshot {
+        let entry = LedgerEntry {
+            last_modified_ledger_seq: 90,
+            data: LedgerEntryData::ContractData(ContractDataEntry {
+                contract: ScAddress::Contract(Hash([1; 32]).into()),
+                key: ScVal::U32(7),
+                durability: ContractDataDurability::Persistent,
+                val: ScVal::Bool(true),
+                ..Default::default()
+            }),
+            // Sponsored, the extension has to survive the snapshot file.
+            ext: LedgerEntryExt::V1(LedgerEntryExtensionV1 {
+                sponsoring_id: SponsorshipDescriptor(Some(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([2; 32]))))),
+                ext: LedgerEntryExtensionV1Ext::V0,
+            }),
+        };
+        Snapshot {
+            protocol_version: 23,
+            sequence_number: 100,
+            network_id: "00".repeat(32),
+            ledger_entries: vec![SnapshotEntry { entry, live_until: Some(200) }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn entries_are_written_as_key_entry_pairs() {
+        let snapshot = snapshot();
+        let json = serde_json::to_value(&snapshot).unwrap();
+        let entry = &snapshot.ledger_entries[0];
+        assert_eq!(
+            json["ledger_entries"],
+            json!([[entry.entry.to_key(), [entry.entry, 200]]])
+        );
+    }
+
+    #[test]
+    fn reads_both_entry_layouts() {
+        let snapshot = snapshot();
+        let keyed = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(serde_json::from_str::<Snapshot>(&keyed).unwrap().ledger_entries, snapshot.ledger_entries);
+
+        let mut compact = serde_json::to_value(&snapshot).unwrap();
+        let entry = &snapshot.ledger_entries[0];
+        compact["ledger_entries"] = json!([{ "entry": entry.entry, "live_until": entry.live_until }]);
+        let read: Snapshot = serde_json::from_value(compact).unwrap();
+        assert_eq!(read.ledger_entries, snapshot.ledger_entries);
+        assert_eq!(read.sequence_number, 100);
+    }
+}
@@ -0,0 +1,108 @@
+use anyhow::{bail, Context};
+use stellar_xdr::curr::{ContractExecutable, Limits, ReadXdr, ScError, ScVal};
+
+/// Formats an `ScVal` compactly for the command output, e.g. `[transfer, GABC…, 100i128]`.
+pub fn format(val: &ScVal) -> String {
+    match val {
+        ScVal::Bool(b) => b.to_string(),
+        ScVal::Void => "void".to_string(),
+        ScVal::Error(error) => format_error(error),
+        ScVal::U32(n) => format!("{n}u32"),
+        ScVal::I32(n) => format!("{n}i32"),
+        ScVal::U64(n) => format!("{n}u64"),
+        ScVal::I64(n) => format!("{n}i64"),
+        ScVal::Timepoint(timepoint) => format!("timepoint({})", timepoint.0),
+        ScVal::Duration(duration) => format!("duration({})", duration.0),
+        ScVal::U128(parts) => format!("{}u128", u128::from(parts)),
+        ScVal::I128(parts) => format!("{}i128", i128::from(parts)),
+        ScVal::U256(parts) => format!("u256(0x{:016x}{:016x}{:016x}{:016x})", parts.hi_hi, parts.hi_lo, parts.lo_hi, parts.lo_lo),
+        ScVal::I256(parts) => format!("i256(0x{:016x}{:016x}{:016x}{:016x})", parts.hi_hi, parts.hi_lo, parts.lo_hi, parts.lo_lo),
+        ScVal::Bytes(bytes) => format!("0x{}", hex(bytes.as_slice())),
+        ScVal::String(string) => format!("{:?}", string.to_utf8_string_lossy()),
+        ScVal::Symbol(symbol) => symbol.to_utf8_string_lossy(),
+        ScVal::Vec(None) | ScVal::Map(None) => "void".to_string(),
+        ScVal::Vec(Some(vec)) => format!("[{}]", vec.iter().map(format).collect::<Vec<_>>().join(", ")),
+        ScVal::Map(Some(map)) => format!(
+            "{{{}}}",
+            map.iter()
+                .map(|entry| format!("{}: {}", format(&entry.key), format(&entry.val)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        ScVal::Address(address) => address.to_string(),
+        ScVal::ContractInstance(instance) => match &instance.executable {
+            ContractExecutable::Wasm(hash) => format!("ContractInstance(wasm {hash})"),
+            ContractExecutable::StellarAsset => "ContractInstance(stellar asset)".to_string(),
+        },
+        ScVal::LedgerKeyContractInstance => "LedgerKeyContractInstance".to_string(),
+        ScVal::LedgerKeyNonce(nonce) => format!("LedgerKeyNonce({})", nonce.nonce),
+    }
+}
+
+/// Decodes a base64 `ScVal` and formats it, falling back to the raw XDR.
+pub fn format_xdr(xdr: &str) -> String {
+    ScVal::from_xdr_base64(xdr, Limits::none())
+        .map(|val| format(&val))
+        .unwrap_or_else(|_| xdr.to_string())
+}
+
+pub fn format_error(error: &ScError) -> String {
+    match error {
+        ScError::Contract(code) => format!("Error(Contract, #{code})"),
+        other => format!("Error({}, {})", other.name(), error_code_name(other)),
+    }
+}
+
+fn error_code_name(error: &ScError) -> &'static str {
+    match error {
+        ScError::Contract(_) => "",
+        ScError::WasmVm(code)
+        | ScError::Context(code)
+        | ScError::Storage(code)
+        | ScError::Object(code)
+        | ScError::Crypto(code)
+        | ScError::Events(code)
+        | ScError::Budget(code)
+        | ScError::Value(code)
+        | ScError::Auth(code) => code.name(),
+    }
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Decodes hex bytes, with or without a `0x` prefix.
+pub fn parse_hex(text: &str) -> anyhow::Result<Vec<u8>> {
+    let digits = text.strip_prefix("0x").unwrap_or(text);
+    if !digits.len().is_multiple_of(2) {
+        bail!("odd number of hex digits in `{text}`");
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|index| {
+            let pair = digits.get(index..index + 2).context("hex must be ascii")?;
+            u8::from_str_radix(pair, 16).with_context(|| format!("invalid hex `{text}`"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hex_with_or_without_prefix() {
+        assert_eq!(parse_hex("0xbeef").unwrap(), [0xbe, 0xef]);
+        assert_eq!(parse_hex("00FF10").unwrap(), [0x00, 0xff, 0x10]);
+        assert_eq!(parse_hex("").unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn rejects_invalid_hex() {
+        assert!(parse_hex("abc").is_err());
+        assert!(parse_hex("zz").is_err());
+        assert!(parse_hex("0x0g").is_err());
+        assert!(parse_hex("é0").is_err());
+    }
+}
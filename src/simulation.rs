@@ -0,0 +1,148 @@
+use anyhow::{anyhow, bail, Context};
+use serde_json::{json, Value};
+use stellar_xdr::curr::{
+    FeeBumpTransactionInnerTx, InvokeHostFunctionOp, Limits, OperationBody, ReadXdr, SorobanAuthorizationEntry,
+    SorobanTransactionData, TransactionEnvelope, TransactionExt, WriteXdr,
+};
+
+use crate::commands::commands::StellarCliCmdName::ContractInvoke;
+use crate::commands::commands::{execute, InvokeArgs};
+use crate::network::StellarNetwork;
+use crate::rpc;
+
+/// What `simulateTransaction` returned for a transaction, needed to assemble it.
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    pub transaction_data: SorobanTransactionData,
+    pub min_resource_fee: i64,
+    /// Authorizations the invocation needs, as recorded by the simulation.
+    pub auth: Vec<SorobanAuthorizationEntry>,
+    /// Instructions the simulation used, unknown when the server reports no cost.
+    pub cpu_insns: Option<u64>,
+    /// Memory the simulation used, unknown when the server reports no cost.
+    pub mem_bytes: Option<u64>,
+}
+
+/// Builds an invocation with the CLI without simulating or submitting it.
+fn build_invoke(args: &InvokeArgs) -> anyhow::Result<TransactionEnvelope> {
+    let res = execute(ContractInvoke(InvokeArgs {
+        build_only: true,
+        ..args.clone()
+    }));
+    if !res.success {
+        bail!("could not build the invocation: {}", res.stderr);
+    }
+    Ok(TransactionEnvelope::from_xdr_base64(res.result.trim(), Limits::none())?)
+}
+
+/// Builds an invocation and assembles it from its simulation, ready to be signed.
+pub fn simulate_invoke(args: &InvokeArgs) -> anyhow::Result<(TransactionEnvelope, Simulation)> {
+    let mut envelope = build_invoke(args)?;
+    let simulated = simulate(args.network, &envelope)?;
+    assemble(&mut envelope, &simulated)?;
+    Ok((envelope, simulated))
+}
+
+/// Simulates a transaction with `simulateTransaction`.
+///
+/// Authorizations already in the transaction are enforced, so signed entries are checked.
+pub fn simulate(network: StellarNetwork, envelope: &TransactionEnvelope) -> anyhow::Result<Simulation> {
+    let transaction = envelope.to_xdr_base64(Limits::none())?;
+    let result = rpc::request(network, "simulateTransaction", json!({ "transaction": transaction }))?;
+    if let Some(error) = result["error"].as_str() {
+        bail!("simulation failed: {error}");
+    }
+    let transaction_data = rpc::str_field(&result, "transactionData")?;
+    let auth = result["results"][0]["auth"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Value::as_str)
+        .map(|xdr| Ok(SorobanAuthorizationEntry::from_xdr_base64(xdr, Limits::none())?))
+        .collect::<anyhow::Result<_>>()?;
+    // Older servers report the cost as strings, newer ones may leave it out.
+    let cost = |field: &str| match &result["cost"][field] {
+        Value::String(value) => value.parse().ok(),
+        value => value.as_u64(),
+    };
+    let transaction_data = SorobanTransactionData::from_xdr_base64(transaction_data, Limits::none())?;
+
+    Ok(Simulation {
+        cpu_insns: cost("cpuInsns"),
+        mem_bytes: cost("memBytes"),
+        transaction_data,
+        min_resource_fee: rpc::str_field(&result, "minResourceFee")?
+            .parse()
+            .context("simulateTransaction returned an invalid minResourceFee")?,
+        auth,
+    })
+}
+
+/// Applies a simulation to its transaction: the resources, the fee and, unless the transaction
+/// already carries some, the recorded authorizations.
+pub fn assemble(envelope: &mut TransactionEnvelope, simulation: &Simulation) -> anyhow::Result<()> {
+    let TransactionEnvelope::Tx(envelope) = envelope else {
+        bail!("only v1 transactions can be simulated");
+    };
+    let tx = &mut envelope.tx;
+    // The fee covers inclusion plus resources, drop the resource fee of an earlier assembly.
+    let previous_resource_fee = match &tx.ext {
+        TransactionExt::V1(data) => data.resource_fee,
+        TransactionExt::V0 => 0,
+    };
+    let inclusion_fee = i64::from(tx.fee) - previous_resource_fee;
+    tx.fee = u32::try_from(inclusion_fee + simulation.min_resource_fee)
+        .map_err(|_| anyhow!("the transaction fee does not fit in 32 bits"))?;
+    tx.ext = TransactionExt::V1(simulation.transaction_data.clone());
+
+    let mut operations = tx.operations.to_vec();
+    if let Some(OperationBody::InvokeHostFunction(op)) = operations.first_mut().map(|operation| &mut operation.body) {
+        if op.auth.is_empty() {
+            op.auth = simulation.auth.clone().try_into()?;
+        }
+    }
+    tx.operations = operations.try_into()?;
+    Ok(())
+}
+
+/// The host function operation of a Soroban transaction.
+pub fn invoke_op(envelope: &TransactionEnvelope) -> anyhow::Result<&InvokeHostFunctionOp> {
+    let TransactionEnvelope::Tx(envelope) = envelope else {
+        bail!("only v1 transactions carry host functions");
+    };
+    match envelope.tx.operations.first().map(|operation| &operation.body) {
+        Some(OperationBody::InvokeHostFunction(op)) => Ok(op),
+        _ => bail!("the transaction does not invoke a host function"),
+    }
+}
+
+/// Resources and footprint of an assembled Soroban transaction.
+pub fn transaction_data(envelope: &TransactionEnvelope) -> Option<&SorobanTransactionData> {
+    let tx = match envelope {
+        TransactionEnvelope::Tx(envelope) => &envelope.tx,
+        TransactionEnvelope::TxFeeBump(envelope) => {
+            let FeeBumpTransactionInnerTx::Tx(inner) = &envelope.tx.inner_tx;
+            &inner.tx
+        }
+        TransactionEnvelope::TxV0(_) => return None,
+    };
+    match &tx.ext {
+        TransactionExt::V1(data) => Some(data),
+        TransactionExt::V0 => None,
+    }
+}
+
+/// Replaces the authorizations of a Soroban transaction.
+pub fn set_auth(envelope: &mut TransactionEnvelope, auth: Vec<SorobanAuthorizationEntry>) -> anyhow::Result<()> {
+    let TransactionEnvelope::Tx(envelope) = envelope else {
+        bail!("only v1 transactions carry host functions");
+    };
+    let mut operations = envelope.tx.operations.to_vec();
+    match operations.first_mut().map(|operation| &mut operation.body) {
+        Some(OperationBody::InvokeHostFunction(op)) => op.auth = auth.try_into()?,
+        _ => bail!("the transaction does not invoke a host function"),
+    }
+    envelope.tx.operations = operations.try_into()?;
+    Ok(())
+}
@@ -0,0 +1,262 @@
+use anyhow::{anyhow, bail, Context};
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use serde_json::Value;
+use stellar_xdr::curr::{
+    Limits, ReadXdr, ScSpecEntry, ScSpecFunctionV0, ScSpecTypeDef, ScSpecUdtErrorEnumCaseV0, ScSpecUdtUnionCaseV0,
+};
+
+use crate::commands::commands::execute;
+use crate::commands::commands::StellarCliCmdName::{self, ContractInterface};
+use crate::network::StellarNetwork;
+use crate::wasm;
+
+/// Fetches the interface of a deployed contract from its `contractspecv0` section.
+pub fn fetch(network: StellarNetwork, contract_id: &str) -> anyhow::Result<Vec<ScSpecEntry>> {
+    read_interface(ContractInterface(network, contract_id.to_string()), contract_id)
+}
+
+/// Reads the interface of a local wasm file.
+pub fn read_wasm(wasm: &str) -> anyhow::Result<Vec<ScSpecEntry>> {
+    let code = std::fs::read(wasm).with_context(|| format!("could not read {wasm}"))?;
+    wasm::decode_section(&code, wasm::SPEC_SECTION)
+}
+
+fn read_interface(cmd: StellarCliCmdName, source: &str) -> anyhow::Result<Vec<ScSpecEntry>> {
+    let res = execute(cmd);
+    if !res.success {
+        bail!("could not read the interface of {source}: {}", res.stderr);
+    }
+    let entries: Vec<String> = serde_json::from_str(&res.result)
+        .map_err(|_| anyhow!("unexpected contract interface output: {}", res.result))?;
+    entries
+        .iter()
+        .map(|entry| Ok(ScSpecEntry::from_xdr_base64(entry, Limits::none())?))
+        .collect()
+}
+
+pub fn function<'a>(spec: &'a [ScSpecEntry], name: &str) -> Option<&'a ScSpecFunctionV0> {
+    spec.iter().find_map(|entry| match entry {
+        ScSpecEntry::FunctionV0(function) if function.name.to_utf8_string_lossy() == name => Some(function),
+        _ => None,
+    })
+}
+
+/// Argument template for a function, e.g. `--to <Address> --amount <I128>`.
+pub fn args_template(function: &ScSpecFunctionV0) -> String {
+    function
+        .inputs
+        .iter()
+        .map(|input| format!("--{} <{}>", input.name.to_utf8_string_lossy(), type_name(&input.type_)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Cases of the contract's error enums with the given code, with the name of their enum.
+pub fn error_cases(spec: &[ScSpecEntry], code: u32) -> Vec<(String, &ScSpecUdtErrorEnumCaseV0)> {
+    spec.iter()
+        .filter_map(|entry| match entry {
+            ScSpecEntry::UdtErrorEnumV0(error_enum) => Some(error_enum),
+            _ => None,
+        })
+        .flat_map(|error_enum| {
+            error_enum
+                .cases
+                .iter()
+                .filter(move |case| case.value == code)
+                .map(|case| (error_enum.name.to_utf8_string_lossy(), case))
+        })
+        .collect()
+}
+
+/// Formats a spec type the way the Rust SDK spells it, e.g. `Option<Vec<Address>>`.
+pub fn type_name(type_def: &ScSpecTypeDef) -> String {
+    match type_def {
+        ScSpecTypeDef::Option(option) => format!("Option<{}>", type_name(&option.value_type)),
+        ScSpecTypeDef::Result(result) => {
+            format!("Result<{}, {}>", type_name(&result.ok_type), type_name(&result.error_type))
+        }
+        ScSpecTypeDef::Vec(vec) => format!("Vec<{}>", type_name(&vec.element_type)),
+        ScSpecTypeDef::Map(map) => format!("Map<{}, {}>", type_name(&map.key_type), type_name(&map.value_type)),
+        ScSpecTypeDef::Tuple(tuple) => format!(
+            "({})",
+            tuple.value_types.iter().map(type_name).collect::<Vec<_>>().join(", ")
+        ),
+        ScSpecTypeDef::BytesN(bytes) => format!("BytesN<{}>", bytes.n),
+        ScSpecTypeDef::Udt(udt) => udt.name.to_utf8_string_lossy(),
+        other => other.name().to_string(),
+    }
+}
+
+/// Kind and name of a spec entry, e.g. `("fn", "transfer")`.
+pub fn entry_name(entry: &ScSpecEntry) -> (&'static str, String) {
+    match entry {
+        ScSpecEntry::FunctionV0(function) => ("fn", function.name.to_utf8_string_lossy()),
+        ScSpecEntry::UdtStructV0(udt) => ("struct", udt.name.to_utf8_string_lossy()),
+        ScSpecEntry::UdtUnionV0(udt) => ("union", udt.name.to_utf8_string_lossy()),
+        ScSpecEntry::UdtEnumV0(udt) => ("enum", udt.name.to_utf8_string_lossy()),
+        ScSpecEntry::UdtErrorEnumV0(udt) => ("error", udt.name.to_utf8_string_lossy()),
+        ScSpecEntry::EventV0(event) => ("event", event.name.to_utf8_string_lossy()),
+    }
+}
+
+/// Signature of a function, e.g. `fn transfer(from: Address, to: Address, amount: I128) -> Void`.
+pub fn signature(function: &ScSpecFunctionV0) -> String {
+    let inputs: Vec<String> = function
+        .inputs
+        .iter()
+        .map(|input| format!("{}: {}", input.name.to_utf8_string_lossy(), type_name(&input.type_)))
+        .collect();
+    let output = function.outputs.first().map(type_name).unwrap_or_else(|| "()".to_string());
+    format!("fn {}({}) -> {output}", function.name.to_utf8_string_lossy(), inputs.join(", "))
+}
+
+/// One line rendering of a spec entry, e.g. `struct Allowance { amount: I128, expiration: U32 }`.
+pub fn describe(entry: &ScSpecEntry) -> String {
+    let join = |items: Vec<String>| items.join(", ");
+    match entry {
+        ScSpecEntry::FunctionV0(function) => signature(function),
+        ScSpecEntry::UdtStructV0(udt) => format!(
+            "struct {} {{ {} }}",
+            udt.name.to_utf8_string_lossy(),
+            join(udt.fields.iter()
+                .map(|field| format!("{}: {}", field.name.to_utf8_string_lossy(), type_name(&field.type_)))
+                .collect())
+        ),
+        ScSpecEntry::UdtUnionV0(udt) => format!(
+            "union {} {{ {} }}",
+            udt.name.to_utf8_string_lossy(),
+            join(udt.cases.iter()
+                .map(|case| match case {
+                    ScSpecUdtUnionCaseV0::VoidV0(case) => case.name.to_utf8_string_lossy(),
+                    ScSpecUdtUnionCaseV0::TupleV0(case) => format!(
+                        "{}({})",
+                        case.name.to_utf8_string_lossy(),
+                        join(case.type_.iter().map(type_name).collect())
+                    ),
+                })
+                .collect())
+        ),
+        ScSpecEntry::UdtEnumV0(udt) => format!(
+            "enum {} {{ {} }}",
+            udt.name.to_utf8_string_lossy(),
+            join(udt.cases.iter()
+                .map(|case| format!("{} = {}", case.name.to_utf8_string_lossy(), case.value))
+                .collect())
+        ),
+        ScSpecEntry::UdtErrorEnumV0(udt) => format!(
+            "error {} {{ {} }}",
+            udt.name.to_utf8_string_lossy(),
+            join(udt.cases.iter()
+                .map(|case| format!("{} = {}", case.name.to_utf8_string_lossy(), case.value))
+                .collect())
+        ),
+        ScSpecEntry::EventV0(event) => format!(
+            "event {} [{}] ({})",
+            event.name.to_utf8_string_lossy(),
+            join(event.prefix_topics.iter().map(|topic| topic.to_utf8_string_lossy()).collect()),
+            join(event.params.iter()
+                .map(|param| format!("{}: {}", param.name.to_utf8_string_lossy(), type_name(&param.type_)))
+                .collect())
+        ),
+    }
+}
+
+/// Lists the functions and types added, removed or changed between two interfaces.
+///
+/// Doc comments are ignored, only the shape of the interface is compared.
+pub fn diff(old: &[ScSpecEntry], new: &[ScSpecEntry]) -> Vec<Line<'static>> {
+    let find = |spec: &[ScSpecEntry], name: &(&str, String)| spec.iter().find(|entry| entry_name(entry) == *name).cloned();
+
+    let mut lines = Vec::new();
+    for entry in new {
+        let name = entry_name(entry);
+        match find(old, &name) {
+            None => lines.push(Line::from(format!("+ {}", describe(entry)).green())),
+            Some(old_entry) if without_docs(&old_entry) != without_docs(entry) => {
+                lines.push(Line::from(format!("~ {}", describe(entry)).yellow()));
+                lines.push(Line::from(format!("    was {}", describe(&old_entry)).dim()));
+            }
+            Some(_) => {}
+        }
+    }
+    for entry in old {
+        if find(new, &entry_name(entry)).is_none() {
+            lines.push(Line::from(format!("- {}", describe(entry)).red()));
+        }
+    }
+    lines
+}
+
+fn without_docs(entry: &ScSpecEntry) -> Value {
+    fn strip(value: &mut Value) {
+        match value {
+            Value::Object(object) => {
+                object.remove("doc");
+                object.values_mut().for_each(strip);
+            }
+            Value::Array(array) => array.iter_mut().for_each(strip),
+            _ => {}
+        }
+    }
+    let mut value = serde_json::to_value(entry).unwrap_or_default();
+    strip(&mut value);
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_xdr::curr::{ScSpecFunctionInputV0, ScSymbol};
+
+    use super::*;
+
+    fn function(name: &str, inputs: &[(&str, ScSpecTypeDef)], doc: &str) -> ScSpecEntry {
+        ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+            doc: doc.try_into().unwrap(),
+            name: ScSymbol(name.try_into().unwrap()),
+            inputs: inputs
+                .iter()
+                .map(|(name, type_)| ScSpecFunctionInputV0 {
+                    name: (*name).try_into().unwrap(),
+                    type_: type_.clone(),
+                    ..Default::default()
+                })
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+            outputs: Default::default(),
+        })
+    }
+
+    fn rendered(lines: Vec<Line>) -> Vec<String> {
+        lines.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn diff_lists_added_changed_and_removed_entries() {
+        let old = [
+            function("balance", &[("id", ScSpecTypeDef::Address)], ""),
+            function("burn", &[("from", ScSpecTypeDef::Address)], ""),
+        ];
+        let new = [
+            function("balance", &[("id", ScSpecTypeDef::Address), ("at", ScSpecTypeDef::U32)], ""),
+            function("mint", &[("to", ScSpecTypeDef::Address)], ""),
+        ];
+        assert_eq!(
+            rendered(diff(&old, &new)),
+            [
+                "~ fn balance(id: Address, at: U32) -> ()",
+                "    was fn balance(id: Address) -> ()",
+                "+ fn mint(to: Address) -> ()",
+                "- fn burn(from: Address) -> ()",
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_ignores_doc_changes() {
+        let old = [function("balance", &[("id", ScSpecTypeDef::Address)], "Balance of id")];
+        let new = [function("balance", &[("id", ScSpecTypeDef::Address)], "Returns the balance")];
+        assert!(diff(&old, &new).is_empty());
+    }
+}
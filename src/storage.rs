@@ -0,0 +1,299 @@
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use stellar_xdr::curr::{
+    ContractDataDurability, ContractExecutable, LedgerEntryData, LedgerKey, LedgerKeyContractData, ScAddress,
+    ScMapEntry, ScVal,
+};
+
+use crate::ledger_entry;
+use crate::network::StellarNetwork;
+use crate::sandbox::{Snapshot, SnapshotEntry};
+use crate::scval;
+
+/// Storage keys of a contract the explorer knows of: its instance, plus the data entries seen in
+/// footprints and reads, Soroban RPC can't list a contract's storage.
+pub fn known_keys(contract: &ScAddress, seen: &[LedgerKey]) -> Vec<LedgerKey> {
+    let mut keys = vec![LedgerKey::ContractData(LedgerKeyContractData {
+        contract: contract.clone(),
+        key: ScVal::LedgerKeyContractInstance,
+        durability: ContractDataDurability::Persistent,
+    })];
+    for key in seen {
+        if matches!(key, LedgerKey::ContractData(data) if data.contract == *contract) && !keys.contains(key) {
+            keys.push(key.clone());
+        }
+    }
+    keys
+}
+
+/// Snapshots the entries behind `keys` at the latest ledger, in the sandbox's snapshot format.
+pub fn snapshot(network: StellarNetwork, keys: &[LedgerKey]) -> anyhow::Result<Snapshot> {
+    let mut snapshot = Snapshot::default();
+    snapshot.fetch(network, keys)?;
+    Ok(snapshot)
+}
+
+/// Renders what changed between two snapshots: added, removed and modified entries with their
+/// decoded values, and TTL changes. Instance storage is compared key by key.
+///
+/// With `live` the later snapshot was fetched over RPC, which returns nothing for archived and
+/// evicted entries either, so entries it lacks are shown as missing rather than removed.
+pub fn diff(before: &Snapshot, after: &Snapshot, live: bool) -> Vec<Line<'static>> {
+    let mut keys: Vec<LedgerKey> = Vec::new();
+    for entry in before.ledger_entries.iter().chain(after.ledger_entries.iter()) {
+        let key = entry.entry.to_key();
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    let (mut added, mut removed, mut missing, mut modified, mut extended) = (0, 0, 0, 0, 0);
+    let mut lines = Vec::new();
+    for key in &keys {
+        let description = ledger_entry::describe_key(key);
+        match (before.entry(key), after.entry(key)) {
+            (None, Some(new)) => {
+                added += 1;
+                lines.push(Line::from(format!("+ {description}").green()));
+                lines.push(Line::raw(format!("    = {}", value(&new.entry.data))));
+                lines.extend(live_until(None, new));
+            }
+            (Some(old), None) if live => {
+                missing += 1;
+                lines.push(Line::from(format!("? {description}, missing (deleted or archived)").magenta()));
+                lines.push(Line::from(format!("    was {}", value(&old.entry.data)).dim()));
+                if let Some(live_until) = old.live_until {
+                    lines.push(Line::from(format!("    was live until ledger {live_until}").dim()));
+                }
+            }
+            (Some(old), None) => {
+                removed += 1;
+                lines.push(Line::from(format!("- {description}").red()));
+                lines.push(Line::from(format!("    was {}", value(&old.entry.data)).dim()));
+            }
+            (Some(old), Some(new)) => {
+                let changes = value_changes(&old.entry.data, &new.entry.data);
+                let ttl = live_until(Some(old), new);
+                if changes.is_empty() && ttl.is_none() {
+                    continue;
+                }
+                if changes.is_empty() {
+                    extended += 1;
+                    lines.push(Line::raw(format!("  {description}")));
+                } else {
+                    modified += 1;
+                    lines.push(Line::from(format!("~ {description}").yellow()));
+                }
+                lines.extend(changes);
+                lines.extend(ttl);
+            }
+            (None, None) => {}
+        }
+    }
+
+    let mut header = vec![
+        Line::from(format!("Storage diff, ledger {} → {}", before.sequence_number, after.sequence_number).bold()),
+        Line::raw(if live {
+            format!(
+                "{added} added, {missing} missing (deleted or archived), {modified} modified, {extended} with only their TTL changed, of {} entries",
+                keys.len()
+            )
+        } else {
+            format!(
+                "{added} added, {removed} removed, {modified} modified, {extended} with only their TTL changed, of {} entries",
+                keys.len()
+            )
+        }),
+    ];
+    if lines.is_empty() {
+        header.push(Line::from("No changes".green()));
+    }
+    header.extend(lines);
+    header
+}
+
+fn value(data: &LedgerEntryData) -> String {
+    match data {
+        LedgerEntryData::ContractData(data) => scval::format(&data.val),
+        other => ledger_entry::describe_data(other),
+    }
+}
+
+/// Lines describing how an entry's value changed, empty when it didn't.
+fn value_changes(old: &LedgerEntryData, new: &LedgerEntryData) -> Vec<Line<'static>> {
+    if old == new {
+        return Vec::new();
+    }
+    let instances = match (old, new) {
+        (LedgerEntryData::ContractData(old), LedgerEntryData::ContractData(new)) => match (&old.val, &new.val) {
+            (ScVal::ContractInstance(old), ScVal::ContractInstance(new)) => Some((old, new)),
+            _ => None,
+        },
+        _ => None,
+    };
+    let Some((old, new)) = instances else {
+        return vec![
+            Line::from(format!("    before: {}", value(old)).dim()),
+            Line::raw(format!("    after:  {}", value(new))),
+        ];
+    };
+
+    let mut lines = Vec::new();
+    if old.executable != new.executable {
+        lines.push(Line::raw(format!(
+            "    executable {} → {}",
+            executable(&old.executable),
+            executable(&new.executable)
+        )));
+    }
+    let old_storage = old.storage.as_ref().map(|map| map.as_slice()).unwrap_or_default();
+    let new_storage = new.storage.as_ref().map(|map| map.as_slice()).unwrap_or_default();
+    for entry in new_storage {
+        match storage_value(old_storage, &entry.key) {
+            None => lines.push(Line::from(
+                format!("    + instance {} = {}", scval::format(&entry.key), scval::format(&entry.val)).green(),
+            )),
+            Some(old_val) if *old_val != entry.val => lines.push(Line::from(
+                format!(
+                    "    ~ instance {}: {} → {}",
+                    scval::format(&entry.key),
+                    scval::format(old_val),
+                    scval::format(&entry.val)
+                )
+                .yellow(),
+            )),
+            Some(_) => {}
+        }
+    }
+    for entry in old_storage {
+        if storage_value(new_storage, &entry.key).is_none() {
+            lines.push(Line::from(
+                format!("    - instance {} was {}", scval::format(&entry.key), scval::format(&entry.val)).red(),
+            ));
+        }
+    }
+    lines
+}
+
+fn storage_value<'a>(storage: &'a [ScMapEntry], key: &ScVal) -> Option<&'a ScVal> {
+    storage.iter().find(|entry| entry.key == *key).map(|entry| &entry.val)
+}
+
+fn executable(executable: &ContractExecutable) -> String {
+    match executable {
+        ContractExecutable::Wasm(hash) => format!("wasm {hash}"),
+        ContractExecutable::StellarAsset => "stellar asset".to_string(),
+    }
+}
+
+fn live_until(old: Option<&SnapshotEntry>, new: &SnapshotEntry) -> Option<Line<'static>> {
+    let new_live_until = new.live_until?;
+    match old.and_then(|old| old.live_until) {
+        None => Some(Line::from(format!("    live until ledger {new_live_until}").dim())),
+        Some(old_live_until) if old_live_until != new_live_until => {
+            Some(Line::from(format!("    live until ledger {old_live_until} → {new_live_until}").dim()))
+        }
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_xdr::curr::{ContractDataEntry, Hash, LedgerEntry, LedgerEntryExt, ScContractInstance, ScMap, ScSymbol};
+
+    use super::*;
+
+    fn contract() -> ScAddress {
+        ScAddress::Contract(Hash([1; 32]).into())
+    }
+
+    fn symbol(text: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(text.try_into().unwrap()))
+    }
+
+    fn entry(key: ScVal, val: ScVal, live_until: u32) -> SnapshotEntry {
+        SnapshotEntry {
+            entry: LedgerEntry {
+                last_modified_ledger_seq: 1,
+                data: LedgerEntryData::ContractData(ContractDataEntry {
+                    contract: contract(),
+                    key,
+                    durability: ContractDataDurability::Persistent,
+                    val,
+                    ..Default::default()
+                }),
+                ext: LedgerEntryExt::V0,
+            },
+            live_until: Some(live_until),
+        }
+    }
+
+    fn instance(storage: Vec<(&str, u32)>) -> ScVal {
+        let storage = storage
+            .into_iter()
+            .map(|(key, val)| ScMapEntry { key: symbol(key), val: ScVal::U32(val) })
+            .collect::<Vec<_>>();
+        ScVal::ContractInstance(ScContractInstance {
+            executable: ContractExecutable::StellarAsset,
+            storage: Some(ScMap(storage.try_into().unwrap())),
+        })
+    }
+
+    fn snapshot(sequence_number: u32, ledger_entries: Vec<SnapshotEntry>) -> Snapshot {
+        Snapshot { sequence_number, ledger_entries, ..Default::default() }
+    }
+
+    fn rendered(lines: Vec<Line>) -> Vec<String> {
+        lines.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn lists_added_removed_modified_and_extended_entries() {
+        let before = snapshot(10, vec![
+            entry(symbol("Kept"), ScVal::U32(1), 100),
+            entry(symbol("Changed"), ScVal::U32(1), 100),
+            entry(symbol("Gone"), ScVal::U32(1), 100),
+            entry(symbol("Extended"), ScVal::U32(1), 100),
+        ]);
+        let after = snapshot(20, vec![
+            entry(symbol("Kept"), ScVal::U32(1), 100),
+            entry(symbol("Changed"), ScVal::U32(2), 100),
+            entry(symbol("Extended"), ScVal::U32(1), 500),
+            entry(symbol("New"), ScVal::U32(3), 300),
+        ]);
+        let lines = rendered(diff(&before, &after, false));
+        assert_eq!(lines[0], "Storage diff, ledger 10 → 20");
+        assert_eq!(lines[1], "1 added, 1 removed, 1 modified, 1 with only their TTL changed, of 5 entries");
+        assert!(lines.contains(&"    before: 1u32".to_string()) && lines.contains(&"    after:  2u32".to_string()), "{lines:#?}");
+        assert!(lines.iter().any(|line| line.starts_with("- ") && line.contains("Gone")), "{lines:#?}");
+        assert!(lines.contains(&"    live until ledger 100 → 500".to_string()), "{lines:#?}");
+        assert!(lines.iter().any(|line| line.starts_with("+ ") && line.contains("New")), "{lines:#?}");
+        assert!(!lines.iter().any(|line| line.contains("Kept")), "{lines:#?}");
+    }
+
+    #[test]
+    fn entries_missing_from_the_live_ledger_may_be_archived() {
+        let before = snapshot(10, vec![entry(symbol("Balance"), ScVal::U32(1), 100)]);
+        let lines = rendered(diff(&before, &snapshot(20, vec![]), true));
+        assert_eq!(lines[1], "0 added, 1 missing (deleted or archived), 0 modified, 0 with only their TTL changed, of 1 entries");
+        assert!(lines[2].starts_with("? ") && lines[2].ends_with(", missing (deleted or archived)"), "{lines:#?}");
+        assert_eq!(lines[4], "    was live until ledger 100");
+    }
+
+    #[test]
+    fn compares_instance_storage_key_by_key() {
+        let before = snapshot(10, vec![entry(ScVal::LedgerKeyContractInstance, instance(vec![("Admin", 1), ("Paused", 0)]), 100)]);
+        let after = snapshot(20, vec![entry(ScVal::LedgerKeyContractInstance, instance(vec![("Admin", 2), ("Fee", 5)]), 100)]);
+        let lines = rendered(diff(&before, &after, false));
+        assert!(lines.contains(&"    ~ instance Admin: 1u32 → 2u32".to_string()), "{lines:#?}");
+        assert!(lines.contains(&"    + instance Fee = 5u32".to_string()), "{lines:#?}");
+        assert!(lines.contains(&"    - instance Paused was 0u32".to_string()), "{lines:#?}");
+    }
+
+    #[test]
+    fn unchanged_snapshots_have_no_changes() {
+        let before = snapshot(10, vec![entry(symbol("Balance"), ScVal::U32(1), 100)]);
+        let lines = rendered(diff(&before, &before.clone(), false));
+        assert_eq!(lines[2], "No changes");
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::{anyhow, bail};
+use stellar_xdr::curr::ScSpecEntry;
+
+use crate::network::StellarNetwork;
+use crate::{account, ledger_key, spec};
+
+/// Functions of the SEP-41 token interface.
+pub const SEP41_FUNCTIONS: [&str; 10] = [
+    "allowance",
+    "approve",
+    "balance",
+    "transfer",
+    "transfer_from",
+    "burn",
+    "burn_from",
+    "decimals",
+    "name",
+    "symbol",
+];
+
+/// Dashboard actions, each offered when the contract's spec has the function.
+pub const ACTION_OPTIONS: [&str; 6] = ["balance", "allowance", "transfer", "approve", "burn", "mint"];
+
+/// Actions that only read state and are simulated rather than submitted.
+pub const READ_ACTIONS: [&str; 2] = ["balance", "allowance"];
+
+/// Spec of the contract the dashboard last opened, fetched once per contract.
+#[derive(Clone, Debug)]
+pub struct TokenSpec {
+    pub network: StellarNetwork,
+    pub contract_id: String,
+    pub spec: Vec<ScSpecEntry>,
+}
+
+impl TokenSpec {
+    pub fn fetch(network: StellarNetwork, contract_id: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            network,
+            contract_id: contract_id.to_string(),
+            spec: spec::fetch(network, contract_id)?,
+        })
+    }
+
+    pub fn is_for(&self, network: StellarNetwork, contract_id: &str) -> bool {
+        self.network == network && self.contract_id == contract_id
+    }
+
+    /// Interface functions missing from the spec, empty for a SEP-41 token.
+    pub fn missing_functions(&self) -> Vec<&'static str> {
+        SEP41_FUNCTIONS
+            .into_iter()
+            .filter(|function| spec::function(&self.spec, function).is_none())
+            .collect()
+    }
+
+    /// Dashboard actions the contract implements, `mint` is an extension some tokens lack.
+    pub fn actions(&self) -> Vec<&'static str> {
+        ACTION_OPTIONS
+            .into_iter()
+            .filter(|action| spec::function(&self.spec, action).is_some())
+            .collect()
+    }
+}
+
+/// Dashboard field feeding each argument of an action, in argument order.
+fn action_fields(action: &str) -> anyhow::Result<&'static [&'static str]> {
+    Ok(match action {
+        "balance" => &["From / Address"],
+        "allowance" => &["From / Address", "To / Spender"],
+        "transfer" => &["From / Address", "To / Spender", "Amount"],
+        "approve" => &["From / Address", "To / Spender", "Amount", "Expiration ledger"],
+        "burn" => &["From / Address", "Amount"],
+        // `mint` is an admin extension, the admin is the source account.
+        "mint" => &["To / Spender", "Amount"],
+        _ => bail!("unknown token action `{action}`"),
+    })
+}
+
+/// Builds the invocation arguments of `action`, named after the contract's spec and checked
+/// against the argument types.
+pub fn action_args(spec: &[ScSpecEntry], action: &str, value: impl Fn(&str) -> String) -> anyhow::Result<String> {
+    let function = spec::function(spec, action).ok_or_else(|| anyhow!("the contract has no `{action}` function"))?;
+    let fields = action_fields(action)?;
+    if function.inputs.len() != fields.len() {
+        bail!("unexpected signature {}", spec::signature(function));
+    }
+
+    let mut args = Vec::new();
+    for (input, field) in function.inputs.iter().zip(fields) {
+        let mut value = value(field);
+        match *field {
+            "Amount" => {
+                value.parse::<i128>().map_err(|_| anyhow!("invalid amount `{value}`"))?;
+            }
+            "Expiration ledger" => {
+                value.parse::<u32>().map_err(|_| anyhow!("invalid expiration ledger `{value}`"))?;
+            }
+            _ => {
+                value = address(&value)?;
+            }
+        }
+        args.push(format!("--{} {value}", input.name.to_utf8_string_lossy()));
+    }
+    Ok(args.join(" "))
+}
+
+/// Checks an address argument, CLI identities are resolved to their address.
+fn address(value: &str) -> anyhow::Result<String> {
+    if ledger_key::parse_address(value).is_ok() {
+        return Ok(value.to_string());
+    }
+    let address = account::resolve(value)?;
+    ledger_key::parse_address(&address)?;
+    Ok(address)
+}
@@ -0,0 +1,362 @@
+use std::time::{Duration, Instant};
+
+use anyhow::bail;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use serde_json::{json, Value};
+use stellar_xdr::curr::{
+    ContractEvent, DiagnosticEvent, LedgerEntryChange, LedgerEntryChanges, Limits, ReadXdr, ScVal, SorobanTransactionMetaExt,
+    TransactionEnvelope, TransactionMeta, TransactionResult, TransactionResultResult, WriteXdr,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::event::{Event, UiUpdateContent, UiWidget};
+use crate::events;
+use crate::ledger_entry;
+use crate::network::StellarNetwork;
+use crate::rpc::{self, u32_field};
+use crate::scval;
+
+/// How often a sent transaction's status is polled, a bit faster than ledgers close.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Polling gives up after this long, by then the transaction has expired or been dropped.
+const POLL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The parts of a transaction's result meta the lookup view shows.
+#[derive(Clone, Debug, Default)]
+pub struct DecodedMeta {
+    pub return_value: Option<ScVal>,
+    pub events: Vec<ContractEvent>,
+    pub diagnostic_events: Vec<DiagnosticEvent>,
+    /// Ledger entry changes, labelled with the stage or operation that made them.
+    pub changes: Vec<(String, LedgerEntryChange)>,
+    /// Resource fee the transaction was charged after refunds.
+    pub resource_fee_charged: Option<i64>,
+}
+
+impl DecodedMeta {
+    pub fn from_xdr(meta_xdr: &str) -> anyhow::Result<Self> {
+        let mut decoded = DecodedMeta::default();
+        match TransactionMeta::from_xdr_base64(meta_xdr, Limits::none())? {
+            TransactionMeta::V0(operations) => {
+                for (index, operation) in operations.iter().enumerate() {
+                    decoded.push_changes(&format!("op {index}"), &operation.changes);
+                }
+            }
+            TransactionMeta::V1(meta) => {
+                decoded.push_changes("tx", &meta.tx_changes);
+                for (index, operation) in meta.operations.iter().enumerate() {
+                    decoded.push_changes(&format!("op {index}"), &operation.changes);
+                }
+            }
+            TransactionMeta::V2(meta) => {
+                decoded.push_changes("before", &meta.tx_changes_before);
+                for (index, operation) in meta.operations.iter().enumerate() {
+                    decoded.push_changes(&format!("op {index}"), &operation.changes);
+                }
+                decoded.push_changes("after", &meta.tx_changes_after);
+            }
+            TransactionMeta::V3(meta) => {
+                decoded.push_changes("before", &meta.tx_changes_before);
+                for (index, operation) in meta.operations.iter().enumerate() {
+                    decoded.push_changes(&format!("op {index}"), &operation.changes);
+                }
+                decoded.push_changes("after", &meta.tx_changes_after);
+                if let Some(soroban_meta) = meta.soroban_meta {
+                    decoded.resource_fee_charged = resource_fee_charged(&soroban_meta.ext);
+                    decoded.return_value = Some(soroban_meta.return_value);
+                    decoded.events = soroban_meta.events.to_vec();
+                    decoded.diagnostic_events = soroban_meta.diagnostic_events.to_vec();
+                }
+            }
+            TransactionMeta::V4(meta) => {
+                decoded.push_changes("before", &meta.tx_changes_before);
+                for (index, operation) in meta.operations.iter().enumerate() {
+                    decoded.push_changes(&format!("op {index}"), &operation.changes);
+                    decoded.events.extend(operation.events.iter().cloned());
+                }
+                decoded.push_changes("after", &meta.tx_changes_after);
+                // Fee events are emitted per transaction rather than per operation.
+                decoded.events.extend(meta.events.iter().map(|event| event.event.clone()));
+                decoded.diagnostic_events = meta.diagnostic_events.to_vec();
+                if let Some(soroban_meta) = meta.soroban_meta {
+                    decoded.resource_fee_charged = resource_fee_charged(&soroban_meta.ext);
+                    decoded.return_value = soroban_meta.return_value;
+                }
+            }
+        }
+        Ok(decoded)
+    }
+
+    fn push_changes(&mut self, stage: &str, changes: &LedgerEntryChanges) {
+        self.changes
+            .extend(changes.iter().map(|change| (stage.to_string(), change.clone())));
+    }
+}
+
+fn resource_fee_charged(ext: &SorobanTransactionMetaExt) -> Option<i64> {
+    match ext {
+        // Rent is part of the refundable fee.
+        SorobanTransactionMetaExt::V1(ext) => {
+            Some(ext.total_non_refundable_resource_fee_charged + ext.total_refundable_resource_fee_charged)
+        }
+        SorobanTransactionMetaExt::V0 => None,
+    }
+}
+
+/// Extracts the transaction hash the stellar CLI logs after submitting a transaction.
+pub fn find_hash(output: &str) -> Option<String> {
+    output
+        .lines()
+        .filter(|line| line.to_lowercase().contains("transaction hash"))
+        .flat_map(|line| line.split(|c: char| !c.is_ascii_alphanumeric()))
+        .rfind(|word| word.len() == 64 && word.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(str::to_string)
+}
+
+/// Sends a signed transaction with `sendTransaction` and returns its hash once it's pending.
+pub fn send(network: StellarNetwork, envelope: &TransactionEnvelope) -> anyhow::Result<String> {
+    let transaction = envelope.to_xdr_base64(Limits::none())?;
+    let result = rpc::request(network, "sendTransaction", json!({ "transaction": transaction }))?;
+    let hash = rpc::str_field(&result, "hash")?.to_string();
+    match result["status"].as_str().unwrap_or_default() {
+        "PENDING" | "DUPLICATE" => Ok(hash),
+        "ERROR" => {
+            let reason = match result["errorResultXdr"].as_str() {
+                Some(xdr) => TransactionResult::from_xdr_base64(xdr, Limits::none())?.result.name().to_string(),
+                None => "unknown error".to_string(),
+            };
+            bail!("transaction {hash} was rejected: {reason}")
+        }
+        status => bail!("transaction {hash} was not accepted: {status}, try again later"),
+    }
+}
+
+/// Polls `getTransaction` until a sent transaction succeeds or fails, reporting its progress
+/// to the command output.
+pub fn track(network: StellarNetwork, hash: String, sender: UnboundedSender<Event>) {
+    tokio::spawn(async move {
+        let send = |line: Line<'static>| {
+            let update = UiUpdateContent::new(UiWidget::CmdOutput, String::from("Transaction"), line);
+            sender.send(Event::UiUpdate(update)).is_ok()
+        };
+        let started = Instant::now();
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let poll_hash = hash.clone();
+            let poll = tokio::task::spawn_blocking(move || {
+                rpc::request(network, "getTransaction", json!({ "hash": poll_hash }))
+            });
+            let Ok(result) = poll.await else {
+                return;
+            };
+            let waited = started.elapsed().as_secs();
+            let line = match result {
+                Ok(result) => {
+                    let status = result["status"].as_str().unwrap_or_default().to_string();
+                    if status == "SUCCESS" || status == "FAILED" {
+                        let outcome = result["resultXdr"]
+                            .as_str()
+                            .and_then(|xdr| TransactionResult::from_xdr_base64(xdr, Limits::none()).ok())
+                            .map(|result| format!(", {}", result.result.name()))
+                            .unwrap_or_default();
+                        let line = format!(
+                            "{status} in ledger {} after {waited}s{outcome}",
+                            u32_field(&result, "ledger").unwrap_or_default()
+                        );
+                        send(Line::from(if status == "SUCCESS" { line.green().bold() } else { line.red().bold() }));
+                        send(Line::from("Look Up Transaction shows its return value, events and changes".dim()));
+                        return;
+                    }
+                    Line::from(format!("Waiting for {hash}: {status} after {waited}s").dim())
+                }
+                Err(error) => Line::from(format!("Error: {error:#}").red().bold()),
+            };
+            if !send(line) {
+                return;
+            }
+            if started.elapsed() >= POLL_TIMEOUT {
+                send(Line::from(format!("Gave up after {waited}s, the transaction may have expired").red().bold()));
+                return;
+            }
+        }
+    });
+}
+
+/// Fetches a transaction with `getTransaction` and renders its result and meta decoded.
+pub fn render(network: StellarNetwork, hash: &str) -> anyhow::Result<Vec<Line<'static>>> {
+    if hash.is_empty() {
+        bail!("enter a transaction hash");
+    }
+    let result = rpc::request(network, "getTransaction", json!({ "hash": hash }))?;
+    let status = result["status"].as_str().unwrap_or_default();
+    let mut lines = vec![Line::from(format!("Transaction {hash} on {network}").bold())];
+
+    match status {
+        "SUCCESS" => lines.push(Line::from("Status: SUCCESS".green().bold())),
+        "FAILED" => lines.push(Line::from("Status: FAILED".red().bold())),
+        _ => {
+            lines.push(Line::from(format!("Status: {status}").yellow().bold()));
+            lines.push(Line::raw(format!(
+                "Not found up to ledger {}, it may be pending or older than the RPC retention window (oldest ledger {})",
+                u32_field(&result, "latestLedger").unwrap_or_default(),
+                u32_field(&result, "oldestLedger").unwrap_or_default()
+            )));
+            return Ok(lines);
+        }
+    }
+
+    lines.push(Line::raw(format!(
+        "Ledger: {}, created at: {}, application order: {}{}",
+        u32_field(&result, "ledger").unwrap_or_default(),
+        field_string(&result["createdAt"]),
+        field_string(&result["applicationOrder"]),
+        if result["feeBump"].as_bool().unwrap_or_default() { ", fee bump" } else { "" }
+    )));
+
+    if let Some(result_xdr) = result["resultXdr"].as_str() {
+        let tx_result = TransactionResult::from_xdr_base64(result_xdr, Limits::none())?;
+        lines.push(Line::raw(format!("Fee charged: {} stroops", tx_result.fee_charged)));
+        lines.extend(render_result(&tx_result.result)?);
+    }
+
+    let mut meta = match result["resultMetaXdr"].as_str() {
+        Some(meta_xdr) => DecodedMeta::from_xdr(meta_xdr)?,
+        None => DecodedMeta::default(),
+    };
+    if meta.diagnostic_events.is_empty() {
+        meta.diagnostic_events = diagnostic_events_xdr(&result)?;
+    }
+
+    if let Some(return_value) = &meta.return_value {
+        lines.push(Line::raw(format!("Return value: {}", scval::format(return_value))));
+    }
+
+    lines.push(Line::raw(""));
+    lines.push(Line::from(format!("Events ({})", meta.events.len()).bold()));
+    lines.extend(meta.events.iter().map(events::format_contract_event));
+
+    lines.push(Line::raw(""));
+    lines.push(Line::from(format!("Diagnostic events ({})", meta.diagnostic_events.len()).bold()));
+    for event in &meta.diagnostic_events {
+        let mut line = events::format_contract_event(&event.event);
+        if !event.in_successful_contract_call {
+            line.spans.insert(0, "failed call ".red());
+        }
+        lines.push(line);
+    }
+
+    lines.push(Line::raw(""));
+    lines.push(Line::from(format!("Ledger entry changes ({})", meta.changes.len()).bold()));
+    lines.extend(meta.changes.iter().map(|(stage, change)| render_change(stage, change)));
+    Ok(lines)
+}
+
+/// Renders the contract events a transaction emitted.
+pub fn render_events(network: StellarNetwork, hash: &str) -> anyhow::Result<Vec<Line<'static>>> {
+    let result = rpc::request(network, "getTransaction", json!({ "hash": hash }))?;
+    let meta = match result["resultMetaXdr"].as_str() {
+        Some(meta_xdr) => DecodedMeta::from_xdr(meta_xdr)?,
+        None => bail!("transaction {hash} not found, status {}", result["status"].as_str().unwrap_or_default()),
+    };
+    let mut lines = vec![Line::from(format!("Events ({})", meta.events.len()).bold())];
+    lines.extend(meta.events.iter().map(events::format_contract_event));
+    Ok(lines)
+}
+
+fn render_result(result: &TransactionResultResult) -> anyhow::Result<Vec<Line<'static>>> {
+    let mut lines = vec![Line::raw(format!("Result: {}", result.name()))];
+    let operations = match result {
+        TransactionResultResult::TxSuccess(operations) | TransactionResultResult::TxFailed(operations) => {
+            operations.to_vec()
+        }
+        TransactionResultResult::TxFeeBumpInnerSuccess(inner) | TransactionResultResult::TxFeeBumpInnerFailed(inner) => {
+            lines.push(Line::raw(format!(
+                "Inner transaction {}: {}",
+                inner.transaction_hash,
+                inner.result.result.name()
+            )));
+            Vec::new()
+        }
+        _ => Vec::new(),
+    };
+    for (index, operation) in operations.iter().enumerate() {
+        lines.push(Line::raw(format!("  op {index}: {}", serde_json::to_string(operation)?)));
+    }
+    Ok(lines)
+}
+
+/// Diagnostic events the RPC returns next to the meta, when they are not part of it.
+pub(crate) fn diagnostic_events_xdr(result: &Value) -> anyhow::Result<Vec<DiagnosticEvent>> {
+    let events = result["diagnosticEventsXdr"]
+        .as_array()
+        .or_else(|| result["events"]["diagnosticEventsXdr"].as_array());
+    events
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Value::as_str)
+        .map(|xdr| Ok(DiagnosticEvent::from_xdr_base64(xdr, Limits::none())?))
+        .collect()
+}
+
+fn render_change(stage: &str, change: &LedgerEntryChange) -> Line<'static> {
+    let stage = format!("{stage:<7}");
+    match change {
+        LedgerEntryChange::Created(entry) => {
+            Line::from(format!("{stage}created  {}", ledger_entry::describe_data(&entry.data)).green())
+        }
+        LedgerEntryChange::Updated(entry) => {
+            Line::from(format!("{stage}updated  {}", ledger_entry::describe_data(&entry.data)).yellow())
+        }
+        LedgerEntryChange::Removed(key) => {
+            Line::from(format!("{stage}removed  {}", ledger_entry::describe_key(key)).red())
+        }
+        LedgerEntryChange::State(entry) => {
+            Line::from(format!("{stage}before   {}", ledger_entry::describe_data(&entry.data)).dim())
+        }
+        LedgerEntryChange::Restored(entry) => {
+            Line::from(format!("{stage}restored {}", ledger_entry::describe_data(&entry.data)).cyan())
+        }
+    }
+}
+
+/// RPC versions disagree on whether some numbers are strings.
+fn field_string(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        Value::Null => "-".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "c2dd4b2e3e2c50e2f1d3f8b2a4ef2c2f9f0f6ef8c0f3b8d8b6a3a1e7d4c5b6a7";
+
+    #[test]
+    fn finds_the_logged_hash() {
+        let output = format!(
+            "ℹ️ Simulating transaction…\nℹ️ Signing transaction: {HASH}\nℹ️ Transaction hash is {HASH}\n🌎 Submitting transaction…"
+        );
+        assert_eq!(find_hash(&output), Some(HASH.to_string()));
+    }
+
+    #[test]
+    fn ignores_hashes_outside_the_hash_line() {
+        let wasm_hash = "a".repeat(64);
+        let output = format!("Wasm hash: {wasm_hash}\nTransaction hash: {HASH}, explorer link follows");
+        assert_eq!(find_hash(&output), Some(HASH.to_string()));
+        assert_eq!(find_hash(&format!("Wasm hash: {wasm_hash}")), None);
+    }
+
+    #[test]
+    fn ignores_words_that_are_not_hashes() {
+        assert_eq!(find_hash("Transaction hash is pending"), None);
+        assert_eq!(find_hash(&format!("transaction hash {}", &HASH[1..])), None);
+        assert_eq!(find_hash(&format!("transaction hash {}", HASH.replace('c', "g"))), None);
+    }
+}
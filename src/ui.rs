@@ -11,12 +11,12 @@ pub(crate) mod layout {
     use ratatui::layout::{Alignment, Constraint, Layout, Rect};
     use ratatui::style::{Color, Modifier, Style, Styled, Stylize};
     use ratatui::symbols::scrollbar;
-    use ratatui::text::Text;
-    use ratatui::widgets::{Block, BorderType, HighlightSpacing, List, ListItem, Padding, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Tabs, Wrap};
+    use ratatui::text::{Line, Span, Text};
+    use ratatui::widgets::{Block, BorderType, Clear, HighlightSpacing, List, ListItem, Padding, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Tabs, Wrap};
     use strum::IntoEnumIterator;
 
     use crate::app;
-    use crate::app::{App, SelectedTab};
+    use crate::app::{App, CmdOutputState, Form, SelectedTab};
     use crate::app::SelectedTab::{Tab1, Tab2, Tab3, Tab4};
     use crate::event::{UiUpdateContent, UiUpdatePayload, UiWidget};
 
@@ -47,13 +47,17 @@ pub(crate) mod layout {
         match event1.ui_widget() {
             UiWidget::NoUpdate => {}
             UiWidget::Tabs => {}
-            UiWidget::Network => {}
+            UiWidget::Network => {
+                *app.cmd_output_state.network_status = Text::from(event1.ui_update_content().clone());
+            }
             UiWidget::ListSelect => {}
-            UiWidget::CmdOutput => {}
+            UiWidget::CmdOutput => {
+                app.print_lines([event1.ui_update_content().clone()]);
+            }
             UiWidget::Scrollbar => {}
         }
 
-        render_network_widget(frame, &event1, top_right);
+        render_network_widget(frame, &app.cmd_output_state.network_status, top_right);
 
 
         frame.render_widget(
@@ -71,8 +75,8 @@ pub(crate) mod layout {
 
         frame.render_widget(
             Paragraph::new(
-                "Press `Esc`, `Ctrl-C` or `q` to quit.\n\
-                Press left and right to move between tabs.\n",
+                "Press `Esc`, `Ctrl-C` or `q` to quit. Press left and right to move between tabs.\n\
+                Press `Enter` to run a script, `n` to switch network, `o` for build-only mode, `Tab` to scroll the output.\n",
             )
                 .block(
                     Block::bordered()
@@ -86,45 +90,25 @@ pub(crate) mod layout {
             top_area,
         );
 
+        let menu_items = app.selected_tab
+                            .menu_items()
+                            .iter()
+                            .map(|menu_item| match app.menu_item_disabled(*menu_item) {
+                                true => ListItem::new(Line::from(menu_item.to_string().dark_gray())),
+                                false => ListItem::new(menu_item.to_string()),
+                            })
+                            .collect();
+
         frame.render_stateful_widget(
-            match app.selected_tab {
-                Tab1 => list_factory(
-                    vec![
-                        ListItem::new("Extend Instance TTL"),
-                        ListItem::new("Extend Persistence TTL"),
-                        ListItem::new("Extend Temporary TTL"),
-                        ListItem::new("Generate Data Key"),
-                    ],
-                    "Extend TTL Scripts",
-                ),
-                Tab2 => list_factory(
-                    vec![
-                        ListItem::new("Restore Persistent Storage"),
-                        ListItem::new("Restore Contract Instance"),
-                        ListItem::new("Restore Contract Code Hash"),
-                        ListItem::new("Restore Instance Storage"),
-                    ],
-                    "Restore Archived Data Scripts",
-                ),
-                Tab3 => list_factory(
-                    vec![
-                        ListItem::new("Set Persistent Data"),
-                        ListItem::new("Set Instance Data"),
-                        ListItem::new("Extend Persistent TTL"),
-                        ListItem::new("Extend Instance TTL"),
-                    ],
-                    "Contract Invocation Scripts",
-                ),
-                Tab4 => list_factory(
-                    vec![
-                        ListItem::new("Show Contract Data"),
-                        ListItem::new("Show Invocations"),
-                        ListItem::new("Show Storage TTLs"),
-                        ListItem::new("Show Misc data"),
-                    ],
-                    "Display Contract Info Scripts",
-                ),
-            },
+            list_factory(
+                menu_items,
+                match app.selected_tab {
+                    Tab1 => "Extend TTL Scripts",
+                    Tab2 => "Restore Archived Data Scripts",
+                    Tab3 => "Contract Invocation Scripts",
+                    Tab4 => "Display Contract Info Scripts",
+                },
+            ),
             bot_left,
             match selected_tab_index {
                 0 => {
@@ -145,17 +129,21 @@ pub(crate) mod layout {
             },
         );
 
-        render_cmd_output_window(frame, event1, bot_right_console);
+        render_cmd_output_window(frame, &app.cmd_output_state, bot_right_console);
 
         CmdOutputScrollbar::default()
             .render(bot_right_scroll, frame.buffer_mut(), &mut app.cmd_output_state.cmd_output_scrollbar);
+
+        if let Some(form) = &app.form {
+            render_form(frame, form, bot_area);
+        }
     }
 
-    fn render_cmd_output_window(frame: &mut Frame, event1: UiUpdateContent, bot_right_console: Rect) {
+    fn render_cmd_output_window(frame: &mut Frame, cmd_output_state: &CmdOutputState, bot_right_console: Rect) {
         frame.render_widget(
-            Paragraph::new(Text::raw(event1.ui_update_content()))
+            Paragraph::new(cmd_output_state.cmd_output.as_ref().clone())
                 .left_aligned()
-                .scroll((0, 0))
+                .scroll((cmd_output_state.cmd_output_scroll, 0))
                 .wrap(Wrap::default())
                 .block(
                     Block::bordered()
@@ -170,9 +158,59 @@ pub(crate) mod layout {
         );
     }
 
-    fn render_network_widget(frame: &mut Frame, event1: &UiUpdateContent, top_right: Rect) {
+    /// Renders a [`Form`] as a popup over the given area.
+    fn render_form(frame: &mut Frame, form: &Form, area: Rect) {
+        let height = u16::try_from(form.fields.len()).unwrap_or(u16::MAX).saturating_add(5);
+        let [_, popup_row, _] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(height), Constraint::Fill(1)]).areas(area);
+        let [_, popup_area, _] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Percentage(70), Constraint::Fill(1)]).areas(popup_row);
+
+        let mut lines: Vec<Line> = form.fields
+                                       .iter()
+                                       .enumerate()
+                                       .map(|(index, field)| {
+                                           let value = if field.is_picker() {
+                                               format!("◂ {} ▸", field.value)
+                                           } else if index == form.active {
+                                               format!("{}▏", field.value)
+                                           } else {
+                                               field.value.clone()
+                                           };
+                                           let line = Line::from(vec![
+                                               Span::styled(format!("{:>24}: ", field.label), Style::default().add_modifier(Modifier::BOLD)),
+                                               Span::raw(value),
+                                           ]);
+                                           if index == form.active {
+                                               line.style(Style::default().fg(Color::Black).bg(Color::Yellow))
+                                           } else {
+                                               line
+                                           }
+                                       })
+                                       .collect();
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Enter submit · Esc cancel · ↑/↓ field · ←/→ option").dim());
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(
+                    Block::bordered()
+                        .title(form.kind.to_string())
+                        .title_alignment(Alignment::Center)
+                        .title_style(Style::default().add_modifier(Modifier::BOLD))
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(Color::Yellow))
+                        .padding(Padding::horizontal(1)),
+                )
+                .style(Style::default().fg(Color::Yellow).bg(Color::Black)),
+            popup_area,
+        );
+    }
+
+    fn render_network_widget(frame: &mut Frame, network_status: &Text, top_right: Rect) {
         frame.render_widget(
-            Paragraph::new(Text::raw(event1.ui_update_content().to_string()))
+            Paragraph::new(network_status.clone())
                 .right_aligned()
                 .style(Style::default().add_modifier(Modifier::BOLD)
                                        .bg(Color::DarkGray).fg(Color::Yellow))
@@ -0,0 +1,190 @@
+use anyhow::{bail, Context};
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{
+    ContractExecutable, LedgerEntryData, Limited, Limits, ReadXdr, ScEnvMetaEntry, ScMetaEntry, ScSpecEntry, ScVal,
+};
+
+use crate::commands::commands::ContractDataArgs;
+use crate::network::StellarNetwork;
+use crate::{ledger_key, rpc, scval, spec};
+
+/// Custom sections the Soroban SDK embeds in contract wasm.
+pub const SPEC_SECTION: &str = "contractspecv0";
+pub const META_SECTION: &str = "contractmetav0";
+pub const ENV_META_SECTION: &str = "contractenvmetav0";
+
+/// Hash of the wasm a contract instance currently runs, read from its instance entry.
+pub fn deployed_hash(network: StellarNetwork, contract_id: &str) -> anyhow::Result<String> {
+    let key = ledger_key::contract_data_key(&ContractDataArgs {
+        network,
+        contract_id: contract_id.to_string(),
+        ..Default::default()
+    })?;
+    let ledger_entries = rpc::get_ledger_entries(network, &[key])?;
+    let Some(entry) = ledger_entries.entries.first() else {
+        bail!("contract {contract_id} not found on {network}, it may be archived");
+    };
+    match &entry.data {
+        LedgerEntryData::ContractData(data) => match &data.val {
+            ScVal::ContractInstance(instance) => match &instance.executable {
+                ContractExecutable::Wasm(hash) => Ok(hash.to_string()),
+                ContractExecutable::StellarAsset => bail!("{contract_id} is a Stellar Asset Contract, it has no wasm"),
+            },
+            _ => bail!("{contract_id} has no contract instance"),
+        },
+        _ => bail!("unexpected instance entry for {contract_id}"),
+    }
+}
+
+/// Hash of a local wasm file, as `contract upload` would report it.
+pub fn local_hash(path: &str) -> anyhow::Result<String> {
+    let code = std::fs::read(path).with_context(|| format!("could not read {path}"))?;
+    Ok(scval::hex(&Sha256::digest(&code)))
+}
+
+/// Whether the ledger has the code of a wasm hash, transactions using it are only simulated
+/// once it's uploaded.
+pub fn is_uploaded(network: StellarNetwork, wasm_hash: &str) -> anyhow::Result<bool> {
+    let key = ledger_key::contract_code_key(wasm_hash)?;
+    Ok(!rpc::get_ledger_entries(network, &[key])?.entries.is_empty())
+}
+
+/// Concatenated payload of the custom sections called `name`.
+pub fn custom_section(code: &[u8], name: &str) -> anyhow::Result<Vec<u8>> {
+    if code.len() < 8 || &code[..4] != b"\0asm" {
+        bail!("not a wasm module");
+    }
+    let mut payload = Vec::new();
+    let mut pos = 8;
+    while pos < code.len() {
+        let id = code[pos];
+        pos += 1;
+        let size = read_leb128(code, &mut pos)?;
+        let end = pos.checked_add(size).filter(|end| *end <= code.len()).context("truncated wasm section")?;
+        if id == 0 {
+            let name_len = read_leb128(code, &mut pos)?;
+            let name_end = pos.checked_add(name_len).filter(|name_end| *name_end <= end).context("invalid section name")?;
+            if &code[pos..name_end] == name.as_bytes() {
+                payload.extend_from_slice(&code[name_end..end]);
+            }
+        }
+        pos = end;
+    }
+    Ok(payload)
+}
+
+/// Decodes the XDR entries stored back to back in a custom section.
+pub fn decode_section<T: ReadXdr>(code: &[u8], name: &str) -> anyhow::Result<Vec<T>> {
+    let section = custom_section(code, name)?;
+    let mut reader = Limited::new(section.as_slice(), Limits::none());
+    T::read_xdr_iter(&mut reader)
+        .collect::<Result<_, _>>()
+        .with_context(|| format!("invalid {name} section"))
+}
+
+/// Renders the size, hash, SDK metadata and interface of a wasm file.
+pub fn inspect(path: &str) -> anyhow::Result<Vec<Line<'static>>> {
+    let code = std::fs::read(path).with_context(|| format!("could not read {path}"))?;
+    let mut lines = vec![
+        Line::from(format!("Wasm {path}").bold()),
+        Line::raw(format!("Size: {} bytes, hash: {}", code.len(), scval::hex(&Sha256::digest(&code)))),
+    ];
+
+    for entry in decode_section::<ScEnvMetaEntry>(&code, ENV_META_SECTION)? {
+        let ScEnvMetaEntry::ScEnvMetaKindInterfaceVersion(version) = entry;
+        lines.push(Line::raw(format!(
+            "Environment interface: protocol {}, pre-release {}",
+            version.protocol, version.pre_release
+        )));
+    }
+    // `rssdkver` is the soroban-sdk version, `rsver` the Rust compiler version.
+    for entry in decode_section::<ScMetaEntry>(&code, META_SECTION)? {
+        let ScMetaEntry::ScMetaV0(meta) = entry;
+        lines.push(Line::raw(format!("{}: {}", meta.key.to_utf8_string_lossy(), meta.val.to_utf8_string_lossy())));
+    }
+
+    let spec: Vec<ScSpecEntry> = decode_section(&code, SPEC_SECTION)?;
+    let (functions, types): (Vec<_>, Vec<_>) = spec
+        .iter()
+        .partition(|entry| matches!(entry, ScSpecEntry::FunctionV0(_)));
+    lines.push(Line::raw(""));
+    lines.push(Line::from(format!("Functions ({})", functions.len()).bold()));
+    lines.extend(functions.into_iter().map(|entry| Line::raw(spec::describe(entry))));
+    lines.push(Line::raw(""));
+    lines.push(Line::from(format!("Types and events ({})", types.len()).bold()));
+    lines.extend(types.into_iter().map(|entry| Line::raw(spec::describe(entry))));
+    Ok(lines)
+}
+
+fn read_leb128(code: &[u8], pos: &mut usize) -> anyhow::Result<usize> {
+    let mut value = 0usize;
+    for shift in (0..35).step_by(7) {
+        let byte = *code.get(*pos).context("truncated wasm")?;
+        *pos += 1;
+        value |= usize::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    bail!("invalid LEB128 integer in wasm")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Module with the given `(id, payload)` sections, section sizes fit in one LEB128 byte.
+    fn module(sections: &[(u8, Vec<u8>)]) -> Vec<u8> {
+        let mut code = b"\0asm\x01\0\0\0".to_vec();
+        for (id, payload) in sections {
+            code.push(*id);
+            code.push(payload.len() as u8);
+            code.extend_from_slice(payload);
+        }
+        code
+    }
+
+    fn custom(name: &str, payload: &[u8]) -> (u8, Vec<u8>) {
+        let mut section = vec![name.len() as u8];
+        section.extend_from_slice(name.as_bytes());
+        section.extend_from_slice(payload);
+        (0, section)
+    }
+
+    #[test]
+    fn concatenates_sections_with_the_name() {
+        let code = module(&[
+            custom("contractspecv0", b"ab"),
+            (1, vec![0]),
+            custom("contractenvmetav0", b"xx"),
+            custom("contractspecv0", b"cd"),
+        ]);
+        assert_eq!(custom_section(&code, "contractspecv0").unwrap(), b"abcd");
+        assert_eq!(custom_section(&code, "contractmetav0").unwrap(), b"");
+    }
+
+    #[test]
+    fn reads_multi_byte_section_sizes() {
+        let payload = vec![7; 200];
+        let mut section = vec![4];
+        section.extend_from_slice(b"spec");
+        section.extend_from_slice(&payload);
+        let mut code = b"\0asm\x01\0\0\0".to_vec();
+        code.push(0);
+        // 205 as LEB128.
+        code.extend_from_slice(&[0xcd, 0x01]);
+        code.extend_from_slice(&section);
+        assert_eq!(custom_section(&code, "spec").unwrap(), payload);
+    }
+
+    #[test]
+    fn rejects_invalid_modules() {
+        assert!(custom_section(b"not wasm", "spec").is_err());
+        let mut truncated = module(&[custom("spec", b"abcd")]);
+        truncated.truncate(truncated.len() - 1);
+        assert!(custom_section(&truncated, "spec").is_err());
+        assert!(custom_section(&module(&[(0, vec![9, b'a'])]), "spec").is_err());
+    }
+}